@@ -0,0 +1,108 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use serde::Serialize;
+use serde_cbor::ser::{BufWrite, IoWrite, SliceWrite};
+use serde_cbor::{Serializer, Value};
+use std::collections::BTreeMap;
+use std::io::{self, IoSlice};
+
+/// A writer that costs about as much per call as a system call on an unbuffered socket.
+struct Socket {
+    written: usize,
+}
+
+impl io::Write for Socket {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_vectored(&[IoSlice::new(buf)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let mut n = 0;
+        for buf in bufs {
+            n += criterion::black_box(&buf[..]).len();
+        }
+        // Simulate the fixed cost of a system call.
+        for _ in 0..64 {
+            criterion::black_box(n);
+        }
+        self.written += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn small_items() -> Value {
+    let mut map = BTreeMap::new();
+    for i in 0..256 {
+        map.insert(
+            Value::Integer(i),
+            Value::Array(vec![
+                Value::Text(format!("item {}", i)),
+                Value::Bool(i % 2 == 0),
+                Value::Float(i as f64 / 3.0),
+            ]),
+        );
+    }
+    Value::Map(map)
+}
+
+fn large_bytes() -> Value {
+    Value::Array(vec![
+        Value::Bytes(vec![0xab; 64 * 1024]),
+        Value::Bytes(vec![0xcd; 256 * 1024]),
+    ])
+}
+
+fn bench_value(c: &mut Criterion, name: &str, value: &Value) {
+    let len = serde_cbor::to_vec(value).unwrap().len();
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(len as u64));
+
+    group.bench_function("io_write", |b| {
+        b.iter(|| {
+            let mut socket = Socket { written: 0 };
+            value
+                .serialize(&mut Serializer::new(IoWrite::new(&mut socket)))
+                .unwrap();
+            socket.written
+        })
+    });
+
+    group.bench_function("buf_write", |b| {
+        b.iter(|| {
+            let mut socket = Socket { written: 0 };
+            let mut writer = BufWrite::new(IoWrite::new(&mut socket));
+            value.serialize(&mut Serializer::new(&mut writer)).unwrap();
+            writer.flush().unwrap();
+            socket.written
+        })
+    });
+
+    group.bench_function("slice_write", |b| {
+        b.iter_batched_ref(
+            || vec![0; len],
+            |buf| {
+                let mut writer = SliceWrite::new(buf);
+                value.serialize(&mut Serializer::new(&mut writer)).unwrap();
+                writer.bytes_written()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("vec", |b| b.iter(|| serde_cbor::to_vec(value).unwrap()));
+    group.finish();
+}
+
+fn bench_small_items(c: &mut Criterion) {
+    bench_value(c, "small_items", &small_items());
+}
+
+fn bench_large_bytes(c: &mut Criterion) {
+    bench_value(c, "large_bytes", &large_bytes());
+}
+
+criterion_group!(benches, bench_small_items, bench_large_bytes);
+criterion_main!(benches);
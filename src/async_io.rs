@@ -0,0 +1,189 @@
+//! Asynchronous reading and writing of CBOR data.
+//!
+//! The [`tokio`](tokio/index.html) module works with the `AsyncRead` and `AsyncWrite` traits of
+//! tokio and is enabled by the `async-tokio` feature. The [`futures`](futures/index.html) module
+//! works with the traits of the `futures` crate and is enabled by the `async-futures` feature.
+//!
+//! Both modules offer the same API:
+//!
+//! * `from_async_reader` decodes a single value from a reader.
+//! * `to_async_writer` encodes a value to a writer.
+//! * `AsyncStreamDeserializer` is a `Stream` of the values of a CBOR sequence.
+//!
+//! The [`codec`](codec/index.html) module, enabled by the `tokio-codec` feature, contains a codec
+//! for the framed transports of `tokio_util`.
+//!
+//! Input is read in chunks and scanned with a [`PushDecoder`](../push/struct.PushDecoder.html)
+//! until a data item is complete, so the reader is never blocked on. Values are encoded into a
+//! buffer before they are written.
+
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
+#[cfg(feature = "async-futures")]
+pub mod futures;
+#[cfg(feature = "async-tokio")]
+pub mod tokio;
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::io;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, ErrorCode, Result};
+use crate::push::{PushDecoder, Status};
+
+/// The number of bytes read at once.
+const CHUNK_LEN: usize = 8 * 1024;
+
+/// Reads data items from an asynchronous reader, given as a function polling it.
+#[derive(Debug, Default)]
+pub(crate) struct Decoder {
+    push: PushDecoder,
+    /// Set at the end of the reader and after errors that can't be recovered from.
+    done: bool,
+}
+
+impl Decoder {
+    /// Polls for the next data item. Returns `None` if the reader ends between two items.
+    pub(crate) fn poll_next<T, F>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut read: F,
+    ) -> Poll<Option<Result<T>>>
+    where
+        T: DeserializeOwned,
+        F: FnMut(&mut Context<'_>, &mut [u8]) -> Poll<io::Result<usize>>,
+    {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        loop {
+            match self.push.decode() {
+                Ok(Status::Item(value)) => return Poll::Ready(Some(Ok(value))),
+                Ok(Status::NeedMore(_)) => {}
+                Err(e) => {
+                    // A syntax error leaves the start of the next item unknown.
+                    self.done = !e.is_data();
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+            match self.poll_fill(cx, &mut read) {
+                Poll::Ready(Ok(true)) => {}
+                Poll::Ready(Ok(false)) => {
+                    self.done = true;
+                    if self.push.buffer().is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Ready(Some(Err(self.eof())));
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Polls for the end of the reader and fails if there is any more data.
+    pub(crate) fn poll_end<F>(&mut self, cx: &mut Context<'_>, mut read: F) -> Poll<Result<()>>
+    where
+        F: FnMut(&mut Context<'_>, &mut [u8]) -> Poll<io::Result<usize>>,
+    {
+        loop {
+            if !self.push.buffer().is_empty() {
+                return Poll::Ready(Err(self.error(ErrorCode::TrailingData)));
+            }
+            match self.poll_fill(cx, &mut read) {
+                Poll::Ready(Ok(true)) => {}
+                Poll::Ready(Ok(false)) => return Poll::Ready(Ok(())),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Reads a chunk into the push decoder and returns `false` at the end of the reader.
+    fn poll_fill<F>(&mut self, cx: &mut Context<'_>, read: &mut F) -> Poll<Result<bool>>
+    where
+        F: FnMut(&mut Context<'_>, &mut [u8]) -> Poll<io::Result<usize>>,
+    {
+        let mut chunk = [0; CHUNK_LEN];
+        loop {
+            match read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => return Poll::Ready(Ok(false)),
+                Poll::Ready(Ok(n)) => {
+                    self.push.feed(&chunk[..n]);
+                    return Poll::Ready(Ok(true));
+                }
+                Poll::Ready(Err(ref e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(Error::io(e))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Returns the offset of the next data item.
+    pub(crate) fn byte_offset(&self) -> u64 {
+        self.push.byte_offset()
+    }
+
+    pub(crate) fn eof(&self) -> Error {
+        self.error(ErrorCode::EofWhileParsingValue)
+    }
+
+    fn error(&self, code: ErrorCode) -> Error {
+        let offset = self.push.byte_offset() + self.push.buffer().len() as u64;
+        Error::syntax(code, offset)
+    }
+}
+
+/// Polls a writer to write all of `buf` and flushes it afterwards.
+pub(crate) fn poll_write_all<W, F, G>(
+    cx: &mut Context<'_>,
+    writer: &mut W,
+    buf: &[u8],
+    written: &mut usize,
+    write: F,
+    flush: G,
+) -> Poll<Result<()>>
+where
+    F: Fn(&mut W, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>,
+    G: Fn(&mut W, &mut Context<'_>) -> Poll<io::Result<()>>,
+{
+    while *written < buf.len() {
+        match write(writer, cx, &buf[*written..]) {
+            Poll::Ready(Ok(0)) => {
+                let e = io::Error::new(io::ErrorKind::WriteZero, "failed to write whole buffer");
+                return Poll::Ready(Err(Error::io(e)));
+            }
+            Poll::Ready(Ok(n)) => *written += n,
+            Poll::Ready(Err(ref e)) if e.kind() == io::ErrorKind::Interrupted => {}
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(Error::io(e))),
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+    flush(writer, cx).map_err(Error::io)
+}
+
+/// Creates a future that calls a function until it returns `Poll::Ready`.
+pub(crate) fn poll_fn<T, F>(f: F) -> PollFn<F>
+where
+    F: FnMut(&mut Context<'_>) -> Poll<T>,
+{
+    PollFn(f)
+}
+
+pub(crate) struct PollFn<F>(F);
+
+impl<F> Unpin for PollFn<F> {}
+
+impl<T, F> Future for PollFn<F>
+where
+    F: FnMut(&mut Context<'_>) -> Poll<T>,
+{
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        (self.0)(cx)
+    }
+}
@@ -0,0 +1,131 @@
+//! A codec for framed transports of `tokio_util`.
+
+use core::marker::PhantomData;
+
+use bytes::{BufMut, BytesMut};
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::de;
+use crate::error::{Error, ErrorCode, Result};
+use crate::push::Scanner;
+use crate::ser::{IoWrite, Serializer};
+
+/// The default limit for the length of a data item, 8 MiB.
+const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+/// Encodes and decodes values as a CBOR sequence for `tokio_util::codec::Framed`.
+///
+/// The decoder finds the end of a data item by scanning the heads of its nested data items as
+/// bytes arrive, and continues where it stopped when more bytes arrive. Only complete items are
+/// deserialized, so every item is deserialized exactly once. If an item is well-formed but can't
+/// be deserialized into `T` it is skipped and an error is returned.
+///
+/// Items longer than the maximum frame length are rejected as soon as their length is known,
+/// before they are buffered completely. The limit also applies to encoded items.
+///
+/// # Examples
+///
+/// ```rust
+/// use bytes::BytesMut;
+/// use serde_cbor::async_io::codec::CborCodec;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = CborCodec::<Vec<u32>>::new();
+/// let mut buf = BytesMut::new();
+/// codec.encode(&[1, 2], &mut buf).unwrap();
+/// assert_eq!(&buf[..], b"\x82\x01\x02");
+///
+/// let mut input = BytesMut::from(&buf[..2]);
+/// assert_eq!(codec.decode(&mut input).unwrap(), None);
+/// input.extend_from_slice(&buf[2..]);
+/// assert_eq!(codec.decode(&mut input).unwrap(), Some(vec![1, 2]));
+/// ```
+#[derive(Debug)]
+pub struct CborCodec<T> {
+    scanner: Scanner,
+    /// The offset of the next data item in the input.
+    offset: u64,
+    max_frame_len: usize,
+    output: PhantomData<fn() -> T>,
+}
+
+impl<T> CborCodec<T> {
+    /// Creates a new codec with a maximum frame length of 8 MiB.
+    pub fn new() -> CborCodec<T> {
+        CborCodec {
+            scanner: Scanner::default(),
+            offset: 0,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            output: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length in bytes of an encoded data item.
+    pub fn max_frame_len(mut self, len: usize) -> Self {
+        self.max_frame_len = len;
+        self
+    }
+}
+
+impl<T> Default for CborCodec<T> {
+    fn default() -> CborCodec<T> {
+        CborCodec::new()
+    }
+}
+
+impl<T> Decoder for CborCodec<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        let needed = self.scanner.scan(src, self.offset)?;
+        if self.scanner.pos().saturating_add(needed.unwrap_or(0)) > self.max_frame_len {
+            return Err(Error::syntax(ErrorCode::FrameLimitExceeded, self.offset));
+        }
+        if let Some(needed) = needed {
+            src.reserve(needed);
+            return Ok(None);
+        }
+        let end = self.scanner.finish();
+        let frame = src.split_to(end);
+        self.offset += end as u64;
+        de::from_slice(&frame).map(Some)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        match self.decode(src)? {
+            Some(value) => Ok(Some(value)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                let offset = self.offset + src.len() as u64;
+                Err(Error::syntax(ErrorCode::EofWhileParsingValue, offset))
+            }
+        }
+    }
+}
+
+impl<T, U> Encoder<U> for CborCodec<T>
+where
+    U: Serialize,
+{
+    type Error = Error;
+
+    fn encode(&mut self, item: U, dst: &mut BytesMut) -> Result<()> {
+        let start = dst.len();
+        let writer = IoWrite::new((&mut *dst).writer());
+        if let Err(e) = item.serialize(&mut Serializer::new(writer)) {
+            dst.truncate(start);
+            return Err(e);
+        }
+        if dst.len() - start > self.max_frame_len {
+            dst.truncate(start);
+            return Err(Error::syntax(ErrorCode::FrameLimitExceeded, 0));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+//! Asynchronous reading and writing with the I/O traits of the `futures` crate.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde_cbor::async_io::futures::{from_async_reader, to_async_writer};
+//!
+//! # futures_executor::block_on(async {
+//! let mut bytes = Vec::new();
+//! to_async_writer(&mut bytes, &vec!["a", "b"]).await.unwrap();
+//! let value: Vec<String> = from_async_reader(&bytes[..]).await.unwrap();
+//! assert_eq!(value, ["a", "b"]);
+//! # });
+//! ```
+
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::io;
+
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use super::{poll_fn, poll_write_all, Decoder};
+use crate::error::Result;
+
+fn poll_read<R>(reader: &mut R, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>>
+where
+    R: AsyncRead + Unpin,
+{
+    Pin::new(reader).poll_read(cx, buf)
+}
+
+/// Decodes a value from CBOR data in an asynchronous reader.
+///
+/// Like `from_reader` this fails if there is any data after the value.
+pub async fn from_async_reader<T, R>(mut reader: R) -> Result<T>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    let mut decoder = Decoder::default();
+    let value = poll_fn(|cx| decoder.poll_next(cx, |cx, buf| poll_read(&mut reader, cx, buf)));
+    let value = match value.await {
+        Some(value) => value?,
+        None => return Err(decoder.eof()),
+    };
+    poll_fn(|cx| decoder.poll_end(cx, |cx, buf| poll_read(&mut reader, cx, buf))).await?;
+    Ok(value)
+}
+
+/// Encodes a value to an asynchronous writer and flushes it.
+pub async fn to_async_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let buf = crate::to_vec(value)?;
+    let mut written = 0;
+    poll_fn(|cx| {
+        poll_write_all(
+            cx,
+            &mut writer,
+            &buf,
+            &mut written,
+            |writer, cx, buf| Pin::new(writer).poll_write(cx, buf),
+            |writer, cx| Pin::new(writer).poll_flush(cx),
+        )
+    })
+    .await
+}
+
+/// A stream of the values of a CBOR sequence in an asynchronous reader.
+///
+/// A data item that is well-formed but can't be deserialized into `T` results in an error and is
+/// skipped. The stream ends after any other error.
+///
+/// # Examples
+///
+/// ```rust
+/// use futures_executor::block_on_stream;
+/// use serde_cbor::async_io::futures::AsyncStreamDeserializer;
+///
+/// let bytes = b"\x01\x02\x03";
+/// let stream = AsyncStreamDeserializer::<_, u32>::new(&bytes[..]);
+/// let values = block_on_stream(stream).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(values, [1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct AsyncStreamDeserializer<R, T> {
+    reader: R,
+    decoder: Decoder,
+    output: PhantomData<fn() -> T>,
+}
+
+impl<R, T> AsyncStreamDeserializer<R, T>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    /// Creates a new stream of the values in `reader`.
+    pub fn new(reader: R) -> AsyncStreamDeserializer<R, T> {
+        AsyncStreamDeserializer {
+            reader,
+            decoder: Decoder::default(),
+            output: PhantomData,
+        }
+    }
+
+    /// Returns the offset in the reader of the next value.
+    pub fn byte_offset(&self) -> u64 {
+        self.decoder.byte_offset()
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Unwraps the reader, discarding any data that has been read but not decoded.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, T> Stream for AsyncStreamDeserializer<R, T>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        let this = self.get_mut();
+        let reader = &mut this.reader;
+        this.decoder
+            .poll_next(cx, |cx, buf| poll_read(reader, cx, buf))
+    }
+}
@@ -1,15 +1,36 @@
 //! Deserialization.
 
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeSet;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(all(feature = "std", feature = "bytes"))]
+use bytes::Bytes;
+use core::cmp;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use core::cmp::Ordering;
+use core::convert::TryFrom;
 use core::f32;
 use core::marker::PhantomData;
+use core::mem;
 use core::result;
 use core::str;
 use half::f16;
-use serde::de;
+use serde::de::{self, IntoDeserializer};
+#[cfg(all(feature = "std", not(feature = "alloc")))]
+use std::collections::BTreeSet;
 #[cfg(feature = "std")]
 use std::io;
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::embedded::{ByteBuf, CBOR_EMBEDDED_NAME};
 use crate::error::{Error, ErrorCode, Result};
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::raw::RAW_VALUE_NAME;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::read::BufferRead;
+#[cfg(all(feature = "std", feature = "bytes"))]
+pub use crate::read::BytesRead;
 #[cfg(not(feature = "unsealed_read_write"))]
 use crate::read::EitherLifetime;
 #[cfg(feature = "unsealed_read_write")]
@@ -19,9 +40,22 @@ pub use crate::read::IoRead;
 use crate::read::Offset;
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub use crate::read::SliceRead;
-pub use crate::read::{MutSliceRead, Read, SliceReadFixed};
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use crate::read::SourceRead;
+pub use crate::read::{MutSliceRead, Read, SliceReadFixed, Source};
+#[cfg(all(feature = "std", feature = "bytes"))]
+use crate::shared::SHARED_BYTES_NAME;
 #[cfg(feature = "tags")]
-use crate::tags::set_tag;
+use crate::tags::CBOR_NEWTYPE_NAME;
+
+/// The tag that marks data as CBOR.
+#[cfg(any(feature = "std", feature = "alloc", feature = "tags"))]
+const SELF_DESCRIBE_TAG: u64 = 55799;
+
+/// The name of the newtype struct that `Value` deserializes as, so that it can receive simple
+/// values and bignums that other types don't support.
+pub(crate) const CBOR_VALUE_NAME: &str = "\0cbor_value";
+
 /// Decodes a value from CBOR data in a slice.
 ///
 /// # Examples
@@ -54,6 +88,34 @@ where
     Ok(value)
 }
 
+/// Decodes a value from CBOR data in `Bytes`.
+///
+/// Byte strings deserialized into a [`SharedBytes`](../shared/struct.SharedBytes.html) share the
+/// buffer of the input, so they can outlive the borrow of `bytes` without being copied.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+/// use serde_cbor::shared::SharedBytes;
+///
+/// // [h'0102', h'030405']
+/// let input = Bytes::from_static(b"\x82\x42\x01\x02\x43\x03\x04\x05");
+/// let parts: Vec<SharedBytes> = serde_cbor::de::from_bytes(&input).unwrap();
+/// assert_eq!(&parts[1][..], [3, 4, 5]);
+/// assert_eq!(parts[1].as_ptr(), input[5..].as_ptr());
+/// ```
+#[cfg(all(feature = "std", feature = "bytes"))]
+pub fn from_bytes<'a, T>(bytes: &'a Bytes) -> Result<T>
+where
+    T: de::Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_bytes(bytes);
+    let value = de::Deserialize::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
 // When the "std" feature is enabled there should be little to no need to ever use this function,
 // as `from_slice` covers all use cases (at the expense of being less efficient).
 /// Decode a value from CBOR data in a mutable slice.
@@ -91,6 +153,56 @@ where
     Ok(value)
 }
 
+/// Checks that a slice starts with a well-formed CBOR data item and returns the item's length.
+///
+/// Bytes after the item are not examined, so calling `validate` repeatedly finds the boundaries
+/// of the items in a CBOR sequence. See `Deserializer::skip_value` for the checks performed.
+///
+/// # Examples
+///
+/// ```
+/// // [1, "a"] followed by 2
+/// let bytes = b"\x82\x01\x61a\x02";
+/// assert_eq!(serde_cbor::validate(bytes).unwrap(), 4);
+/// assert_eq!(serde_cbor::validate(&bytes[4..]).unwrap(), 1);
+/// assert!(serde_cbor::validate(&bytes[..3]).is_err());
+/// ```
+pub fn validate(slice: &[u8]) -> Result<usize> {
+    let mut deserializer = Deserializer::from_slice_with_scratch(slice, &mut []);
+    let end = deserializer.skip_value()?;
+    Ok(end as usize)
+}
+
+/// Checks that `slice` is exactly one data item in deterministic encoding.
+///
+/// Map keys must be sorted by the length of their encoding first if `length_first_keys` is set,
+/// like `Serializer::legacy_canonical` writes them.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub(crate) fn check_deterministic(slice: &[u8], length_first_keys: bool) -> Result<()> {
+    let mut deserializer = Deserializer::from_slice(slice).strict();
+    deserializer.length_first_keys = length_first_keys;
+    let _: de::IgnoredAny = de::Deserialize::deserialize(&mut deserializer)?;
+    deserializer.end()
+}
+
+/// Returns true if the data starts with the self-describe tag 55799.
+///
+/// The tag serves as a magic number that identifies CBOR data and is written by
+/// `Serializer::self_describe`. Its encoding `d9 d9 f7` isn't valid at the start of UTF-8 text
+/// or any other common file format.
+///
+/// # Examples
+///
+/// ```
+/// // 55799([1, 2])
+/// assert!(serde_cbor::is_cbor(b"\xd9\xd9\xf7\x82\x01\x02"));
+/// assert!(!serde_cbor::is_cbor(b"\x82\x01\x02"));
+/// assert!(!serde_cbor::is_cbor(b"{\"a\": 1}"));
+/// ```
+pub fn is_cbor(slice: &[u8]) -> bool {
+    slice.starts_with(&[0xd9, 0xd9, 0xf7])
+}
+
 /// Decodes a value from CBOR data in a reader.
 ///
 /// # Examples
@@ -124,15 +236,71 @@ where
     Ok(value)
 }
 
+/// Decodes a value from CBOR data in a [`Source`](trait.Source.html).
+///
+/// Like `from_reader` this can't borrow data and fails if there is any data after the value.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn from_source<T, S>(source: S) -> Result<T>
+where
+    T: de::DeserializeOwned,
+    S: Source,
+{
+    let mut deserializer = Deserializer::from_source(source);
+    let value = de::Deserialize::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// How the deserializer handles a key that appears more than once in the same map.
+///
+/// Keys are compared by their encoding, so keys that are equal but encoded differently (for
+/// example `0x01` and `0x18 0x01`) are not recognized as duplicates. Use this together with
+/// `Deserializer::strict` to ensure that every key has exactly one encoding.
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Fail with an error at the offset of the repeated key.
+    Error,
+    /// Keep the first entry and skip all later entries with the same key.
+    FirstWins,
+    /// Pass all entries to the visitor, so that maps and `Value` keep the last entry.
+    ///
+    /// Structs with a derived `Deserialize` fail with a duplicate field error instead.
+    ///
+    /// This is the default.
+    LastWins,
+}
+
 /// A Serde `Deserialize`r of CBOR data.
 #[derive(Debug)]
 pub struct Deserializer<R> {
     read: R,
-    remaining_depth: u8,
+    remaining_depth: u32,
+    max_length: Option<usize>,
+    max_elements: Option<usize>,
+    /// Bytes left of the allocation budget.
+    remaining_allocation: Option<usize>,
     accept_named: bool,
     accept_packed: bool,
     accept_standard_enums: bool,
     accept_legacy_enums: bool,
+    strict: bool,
+    strip_self_describe: bool,
+    /// Set while deserializing a `Value`, which accepts all simple values and bignums of any size.
+    value_mode: bool,
+    /// Set while deserializing a `SharedBytes`, until the byte string is read.
+    #[cfg(all(feature = "std", feature = "bytes"))]
+    shared_bytes: bool,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    duplicate_keys: DuplicateKeys,
+    /// In strict mode, expect map keys sorted by the length of their encoding first.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    length_first_keys: bool,
+    /// Bytes consumed from the reader while `recording` is non-zero.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    record: Vec<u8>,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    recording: usize,
 }
 
 #[cfg(feature = "std")]
@@ -146,6 +314,17 @@ where
     }
 }
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<S> Deserializer<SourceRead<S>>
+where
+    S: Source,
+{
+    /// Constructs a `Deserializer` which reads from a `Source`.
+    pub fn from_source(source: S) -> Deserializer<SourceRead<S>> {
+        Deserializer::new(SourceRead::new(source))
+    }
+}
+
 #[cfg(any(feature = "std", feature = "alloc"))]
 impl<'a> Deserializer<SliceRead<'a>> {
     /// Constructs a `Deserializer` which reads from a slice.
@@ -156,6 +335,17 @@ impl<'a> Deserializer<SliceRead<'a>> {
     }
 }
 
+#[cfg(all(feature = "std", feature = "bytes"))]
+impl<'a> Deserializer<BytesRead<'a>> {
+    /// Constructs a `Deserializer` which reads from `Bytes`.
+    ///
+    /// Like with `from_slice` borrowed strings and byte slices will be provided when possible.
+    /// Byte strings deserialized into a `SharedBytes` share the buffer of the input.
+    pub fn from_bytes(bytes: &'a Bytes) -> Deserializer<BytesRead<'a>> {
+        Deserializer::new(BytesRead::new(bytes))
+    }
+}
+
 impl<'a> Deserializer<MutSliceRead<'a>> {
     /// Constructs a `Deserializer` which reads from a mutable slice that doubles as its own
     /// scratch buffer.
@@ -187,10 +377,26 @@ where
         Deserializer {
             read,
             remaining_depth: 128,
+            max_length: None,
+            max_elements: None,
+            remaining_allocation: None,
             accept_named: true,
             accept_packed: true,
             accept_standard_enums: true,
             accept_legacy_enums: true,
+            strict: false,
+            strip_self_describe: false,
+            value_mode: false,
+            #[cfg(all(feature = "std", feature = "bytes"))]
+            shared_bytes: false,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            duplicate_keys: DuplicateKeys::LastWins,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            length_first_keys: false,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            record: Vec::new(),
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            recording: 0,
         }
     }
 
@@ -218,6 +424,137 @@ where
         self
     }
 
+    /// Only accept input in the deterministic encoding described in RFC 8949, section 4.2.
+    ///
+    /// In strict mode the deserializer rejects integers, lengths and tags that are not encoded in
+    /// their shortest form, floats that can be represented exactly in a shorter format,
+    /// indefinite-length items, and maps whose keys are not sorted bytewise by their encoding or
+    /// appear more than once. This guarantees that every accepted value has exactly one
+    /// encoding, as produced by `Serializer::canonical`.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Remove self-describe tags, which mark data as CBOR.
+    ///
+    /// The self-describe tag 55799 is usually written in front of a document, see
+    /// `Serializer::self_describe`. Like all tags it is ignored by most types, but `Value` and
+    /// `tags::Tagged` see it by default. With this option they get the data item inside the tag
+    /// instead. The tag doesn't change the meaning of the data item, so it is removed wherever
+    /// it appears.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use serde_cbor::{Deserializer, Value};
+    ///
+    /// // 55799(1)
+    /// let slice = b"\xd9\xd9\xf7\x01";
+    ///
+    /// let mut de = Deserializer::from_slice(slice).strip_self_describe();
+    /// assert_eq!(Value::deserialize(&mut de).unwrap(), Value::Integer(1));
+    /// ```
+    pub fn strip_self_describe(mut self) -> Self {
+        self.strip_self_describe = true;
+        self
+    }
+
+    /// Choose how to handle keys that appear more than once in a map.
+    ///
+    /// The policy applies to maps, structs and `Value`. With `DuplicateKeys::FirstWins` keys are
+    /// read twice, so they can only borrow from the input when reading from a slice or `Bytes`,
+    /// but not with `from_mut_slice`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use serde_cbor::de::{Deserializer, DuplicateKeys};
+    /// use std::collections::BTreeMap;
+    ///
+    /// // {"a": 1, "a": 2}
+    /// let slice = b"\xa2\x61a\x01\x61a\x02";
+    ///
+    /// let mut de = Deserializer::from_slice(slice).duplicate_keys(DuplicateKeys::FirstWins);
+    /// let map = BTreeMap::<String, u8>::deserialize(&mut de).unwrap();
+    /// assert_eq!(map["a"], 1);
+    ///
+    /// let mut de = Deserializer::from_slice(slice).duplicate_keys(DuplicateKeys::Error);
+    /// assert!(BTreeMap::<String, u8>::deserialize(&mut de).is_err());
+    /// ```
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn duplicate_keys(mut self, policy: DuplicateKeys) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Limit how deeply arrays, maps and tags may be nested.
+    ///
+    /// The top-level item has a depth of one and every array, map or tag adds one level for the
+    /// items it contains. The default limit is 128.
+    pub fn max_depth(mut self, depth: u32) -> Self {
+        self.remaining_depth = depth;
+        self
+    }
+
+    /// Limit the length in bytes of byte and text strings.
+    ///
+    /// For indefinite-length strings the limit applies to the length of all chunks together.
+    /// By default the length is not limited.
+    pub fn max_length(mut self, len: usize) -> Self {
+        self.max_length = Some(len);
+        self
+    }
+
+    /// Limit the number of elements in an array or of entries in a map.
+    ///
+    /// Definite-length collections are rejected as soon as their length is read, before any
+    /// element is deserialized. By default the number of elements is not limited.
+    pub fn max_elements(mut self, len: usize) -> Self {
+        self.max_elements = Some(len);
+        self
+    }
+
+    /// Limit the total length in bytes of all byte and text strings read by this deserializer.
+    ///
+    /// The budget is shared by all values read from the deserializer, including the values of a
+    /// `StreamDeserializer`, and bounds the memory that `from_reader` allocates for strings.
+    /// By default the budget is unlimited.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use serde_cbor::Deserializer;
+    ///
+    /// // ["abc", "def"]
+    /// let slice = b"\x82\x63abc\x63def";
+    ///
+    /// let mut de = Deserializer::from_slice(slice).max_allocation(6);
+    /// assert!(Vec::<String>::deserialize(&mut de).is_ok());
+    ///
+    /// let mut de = Deserializer::from_slice(slice).max_allocation(5);
+    /// assert!(Vec::<String>::deserialize(&mut de).is_err());
+    /// ```
+    pub fn max_allocation(mut self, bytes: usize) -> Self {
+        self.remaining_allocation = Some(bytes);
+        self
+    }
+
+    /// Skips the next data item and returns the offset just past its end.
+    ///
+    /// The item is checked to be well-formed and to satisfy the configured limits and strict
+    /// mode, except that map keys are not checked for order or duplicates. No visitor is called
+    /// and nothing is allocated, so this is considerably faster than deserializing into
+    /// `IgnoredAny`.
+    pub fn skip_value(&mut self) -> Result<u64> {
+        self.skip_item()?;
+        Ok(self.read.offset())
+    }
+
     /// This method should be called after a value has been deserialized to ensure there is no
     /// trailing data in the input source.
     pub fn end(&mut self) -> Result<()> {
@@ -241,7 +578,14 @@ where
     }
 
     fn next(&mut self) -> Result<Option<u8>> {
-        self.read.next()
+        let byte = self.read.next()?;
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.recording > 0 {
+                self.record.extend(byte);
+            }
+        }
+        Ok(byte)
     }
 
     fn peek(&mut self) -> Result<Option<u8>> {
@@ -249,9 +593,59 @@ where
     }
 
     fn consume(&mut self) {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.recording > 0 {
+                if let Ok(byte) = self.read.peek() {
+                    self.record.extend(byte);
+                }
+            }
+        }
         self.read.discard();
     }
 
+    fn read_into(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.read.read_into(buf)?;
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.recording > 0 {
+                self.record.extend_from_slice(buf);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<EitherLifetime<'_, 'de>> {
+        let buf = self.read.read(len)?;
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.recording > 0 {
+                match buf {
+                    EitherLifetime::Long(buf) => self.record.extend_from_slice(buf),
+                    EitherLifetime::Short(buf) => self.record.extend_from_slice(buf),
+                }
+            }
+        }
+        Ok(buf)
+    }
+
+    /// Runs `f` and returns its result together with the bytes it consumed from the reader.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn recorded<F, T>(&mut self, f: F) -> Result<(T, Vec<u8>)>
+    where
+        F: FnOnce(&mut Deserializer<R>) -> Result<T>,
+    {
+        let start = self.record.len();
+        self.recording += 1;
+        let r = f(self);
+        self.recording -= 1;
+        let bytes = self.record[start..].to_vec();
+        if self.recording == 0 {
+            self.record.clear();
+        }
+        r.map(|value| (value, bytes))
+    }
+
     fn error(&self, reason: ErrorCode) -> Error {
         let offset = self.read.offset();
         Error::syntax(reason, offset)
@@ -266,31 +660,115 @@ where
 
     fn parse_u16(&mut self) -> Result<u16> {
         let mut buf = [0; 2];
-        self.read
-            .read_into(&mut buf)
-            .map(|()| u16::from_be_bytes(buf))
+        self.read_into(&mut buf).map(|()| u16::from_be_bytes(buf))
     }
 
     fn parse_u32(&mut self) -> Result<u32> {
         let mut buf = [0; 4];
-        self.read
-            .read_into(&mut buf)
-            .map(|()| u32::from_be_bytes(buf))
+        self.read_into(&mut buf).map(|()| u32::from_be_bytes(buf))
     }
 
     fn parse_u64(&mut self) -> Result<u64> {
         let mut buf = [0; 8];
-        self.read
-            .read_into(&mut buf)
-            .map(|()| u64::from_be_bytes(buf))
+        self.read_into(&mut buf).map(|()| u64::from_be_bytes(buf))
+    }
+
+    /// Returns an error for a non-canonical item whose head of `len` bytes was just read.
+    fn non_canonical(&self, code: ErrorCode, len: u64) -> Error {
+        Error::syntax(code, self.read.offset().saturating_sub(len))
+    }
+
+    // The `parse_arg_*` functions read the argument of an item head. In strict mode they reject
+    // arguments that would also fit into a shorter encoding.
+
+    fn parse_arg_u8(&mut self) -> Result<u8> {
+        let value = self.parse_u8()?;
+        if self.strict && value < 24 {
+            return Err(self.non_canonical(ErrorCode::NonShortestArgument, 2));
+        }
+        Ok(value)
+    }
+
+    fn parse_arg_u16(&mut self) -> Result<u16> {
+        let value = self.parse_u16()?;
+        if self.strict && value <= 0xff {
+            return Err(self.non_canonical(ErrorCode::NonShortestArgument, 3));
+        }
+        Ok(value)
+    }
+
+    fn parse_arg_u32(&mut self) -> Result<u32> {
+        let value = self.parse_u32()?;
+        if self.strict && value <= 0xffff {
+            return Err(self.non_canonical(ErrorCode::NonShortestArgument, 5));
+        }
+        Ok(value)
+    }
+
+    fn parse_arg_u64(&mut self) -> Result<u64> {
+        let value = self.parse_u64()?;
+        if self.strict && value <= 0xffff_ffff {
+            return Err(self.non_canonical(ErrorCode::NonShortestArgument, 9));
+        }
+        Ok(value)
+    }
+
+    /// Rejects the indefinite-length item whose initial byte was just read in strict mode.
+    fn check_indefinite(&self) -> Result<()> {
+        if self.strict {
+            Err(self.non_canonical(ErrorCode::IndefiniteLength, 1))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the length of a string or of the chunks of an indefinite string read so far.
+    fn check_length(&self, len: usize) -> Result<()> {
+        match self.max_length {
+            Some(max) if len > max => Err(self.error(ErrorCode::LengthLimitExceeded)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Takes `len` bytes from the allocation budget before they are read.
+    fn allocate(&mut self, len: usize) -> Result<()> {
+        if let Some(remaining) = self.remaining_allocation {
+            if len > remaining {
+                return Err(self.error(ErrorCode::AllocationLimitExceeded));
+            }
+            self.remaining_allocation = Some(remaining - len);
+        }
+        Ok(())
+    }
+
+    /// Checks the number of elements of an array or entries of a map.
+    fn check_elements(&self, len: usize) -> Result<()> {
+        match self.max_elements {
+            Some(max) if len > max => Err(self.error(ErrorCode::ElementLimitExceeded)),
+            _ => Ok(()),
+        }
     }
 
     fn parse_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        match self.read.read(len)? {
-            EitherLifetime::Long(buf) => visitor.visit_borrowed_bytes(buf),
+        self.check_length(len)?;
+        self.allocate(len)?;
+        match self.read_bytes(len)? {
+            EitherLifetime::Long(buf) => {
+                #[cfg(all(feature = "std", feature = "bytes"))]
+                {
+                    if mem::replace(&mut self.shared_bytes, false) {
+                        if let Some(bytes) = self.read.shared_bytes(buf) {
+                            return crate::shared::lend(bytes, || {
+                                visitor.visit_borrowed_bytes(buf)
+                            });
+                        }
+                    }
+                }
+                visitor.visit_borrowed_bytes(buf)
+            }
             EitherLifetime::Short(buf) => visitor.visit_bytes(buf),
         }
     }
@@ -299,16 +777,25 @@ where
     where
         V: de::Visitor<'de>,
     {
+        self.check_indefinite()?;
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.recording > 0 {
+                let buf = self.parse_recorded_chunks(2)?;
+                return visitor.visit_byte_buf(buf);
+            }
+        }
         self.read.clear_buffer();
+        let mut total = 0usize;
         loop {
             let byte = self.parse_u8()?;
             let len = match byte {
                 0x40..=0x57 => byte as usize - 0x40,
-                0x58 => self.parse_u8()? as usize,
-                0x59 => self.parse_u16()? as usize,
-                0x5a => self.parse_u32()? as usize,
+                0x58 => self.parse_arg_u8()? as usize,
+                0x59 => self.parse_arg_u16()? as usize,
+                0x5a => self.parse_arg_u32()? as usize,
                 0x5b => {
-                    let len = self.parse_u64()?;
+                    let len = self.parse_arg_u64()?;
                     if len > usize::max_value() as u64 {
                         return Err(self.error(ErrorCode::LengthOutOfRange));
                     }
@@ -318,6 +805,9 @@ where
                 _ => return Err(self.error(ErrorCode::UnexpectedCode)),
             };
 
+            total = total.saturating_add(len);
+            self.check_length(total)?;
+            self.allocate(len)?;
             self.read.read_to_buffer(len)?;
         }
 
@@ -327,6 +817,41 @@ where
         }
     }
 
+    /// Reads the chunks of an indefinite-length string of the given major type into a new buffer.
+    ///
+    /// This is used while recording, as the recording must see every chunk in the order it
+    /// appears in the input and not just the joined contents of the scratch buffer.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn parse_recorded_chunks(&mut self, major: u8) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        loop {
+            let byte = self.parse_u8()?;
+            if byte == 0xff {
+                return Ok(buf);
+            }
+            if byte >> 5 != major {
+                return Err(self.error(ErrorCode::UnexpectedCode));
+            }
+            let len = match byte & 0x1f {
+                len @ 0x00..=0x17 => len as usize,
+                0x18 => self.parse_arg_u8()? as usize,
+                0x19 => self.parse_arg_u16()? as usize,
+                0x1a => self.parse_arg_u32()? as usize,
+                0x1b => {
+                    let len = self.parse_arg_u64()?;
+                    usize::try_from(len).map_err(|_| self.error(ErrorCode::LengthOutOfRange))?
+                }
+                _ => return Err(self.error(ErrorCode::UnexpectedCode)),
+            };
+            self.check_length(buf.len().saturating_add(len))?;
+            self.allocate(len)?;
+            match self.read_bytes(len)? {
+                EitherLifetime::Long(chunk) => buf.extend_from_slice(chunk),
+                EitherLifetime::Short(chunk) => buf.extend_from_slice(chunk),
+            }
+        }
+    }
+
     fn convert_str<'a>(buf: &'a [u8], buf_end_offset: u64) -> Result<&'a str> {
         match str::from_utf8(buf) {
             Ok(s) => Ok(s),
@@ -342,8 +867,10 @@ where
     where
         V: de::Visitor<'de>,
     {
+        self.check_length(len)?;
+        self.allocate(len)?;
         if let Some(offset) = self.read.offset().checked_add(len as u64) {
-            match self.read.read(len)? {
+            match self.read_bytes(len)? {
                 EitherLifetime::Long(buf) => {
                     let s = Self::convert_str(buf, offset)?;
                     visitor.visit_borrowed_str(s)
@@ -366,16 +893,26 @@ where
     where
         V: de::Visitor<'de>,
     {
+        self.check_indefinite()?;
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.recording > 0 {
+                let buf = self.parse_recorded_chunks(3)?;
+                let s = Self::convert_str(&buf, self.read.offset())?;
+                return visitor.visit_str(s);
+            }
+        }
         self.read.clear_buffer();
+        let mut total = 0usize;
         loop {
             let byte = self.parse_u8()?;
             let len = match byte {
                 0x60..=0x77 => byte as usize - 0x60,
-                0x78 => self.parse_u8()? as usize,
-                0x79 => self.parse_u16()? as usize,
-                0x7a => self.parse_u32()? as usize,
+                0x78 => self.parse_arg_u8()? as usize,
+                0x79 => self.parse_arg_u16()? as usize,
+                0x7a => self.parse_arg_u32()? as usize,
                 0x7b => {
-                    let len = self.parse_u64()?;
+                    let len = self.parse_arg_u64()?;
                     if len > usize::max_value() as u64 {
                         return Err(self.error(ErrorCode::LengthOutOfRange));
                     }
@@ -385,6 +922,9 @@ where
                 _ => return Err(self.error(ErrorCode::UnexpectedCode)),
             };
 
+            total = total.saturating_add(len);
+            self.check_length(total)?;
+            self.allocate(len)?;
             self.read.read_to_buffer(len)?;
         }
 
@@ -401,73 +941,252 @@ where
         }
     }
 
-    #[cfg(feature = "tags")]
+    /// Passes a tagged value to a `Value`, other types only see the value. Self-describe tags are
+    /// passed on only if they aren't stripped.
+    #[cfg_attr(not(feature = "tags"), allow(unused_variables))]
     fn handle_tagged_value<V>(&mut self, tag: u64, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        self.recursion_checked(|d| {
-            set_tag(Some(tag));
-            let r = visitor.visit_newtype_struct(d);
-            set_tag(None);
-            r
-        })
+        #[cfg(feature = "tags")]
+        {
+            if self.value_mode && !(tag == SELF_DESCRIBE_TAG && self.strip_self_describe) {
+                return self
+                    .recursion_checked(|de| visitor.visit_enum(TagAccess { de, tag, index: 0 }));
+            }
+        }
+        self.recursion_checked(|de| de.parse_value(visitor))
+    }
+
+    /// Consumes the head of a tag and returns the tag, or returns `None` if the next data item
+    /// isn't tagged.
+    #[cfg(any(feature = "std", feature = "alloc", feature = "tags"))]
+    fn parse_tag(&mut self) -> Result<Option<u64>> {
+        loop {
+            match self.parse_tag_head()? {
+                Some(SELF_DESCRIBE_TAG) if self.strip_self_describe => {}
+                tag => return Ok(tag),
+            }
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc", feature = "tags"))]
+    fn parse_tag_head(&mut self) -> Result<Option<u64>> {
+        let tag = match self.peek()? {
+            Some(byte @ 0xc0..=0xd7) => {
+                self.consume();
+                u64::from(byte) - 0xc0
+            }
+            Some(0xd8) => {
+                self.consume();
+                self.parse_arg_u8()?.into()
+            }
+            Some(0xd9) => {
+                self.consume();
+                self.parse_arg_u16()?.into()
+            }
+            Some(0xda) => {
+                self.consume();
+                self.parse_arg_u32()?.into()
+            }
+            Some(0xdb) => {
+                self.consume();
+                self.parse_arg_u64()?
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(tag))
     }
 
-    #[cfg(not(feature = "tags"))]
-    fn handle_tagged_value<V>(&mut self, _tag: u64, visitor: V) -> Result<V::Value>
+    /// Passes a tagged value as an enum variant and an untagged value as a newtype struct to the
+    /// visitor of a `Tagged`.
+    #[cfg(feature = "tags")]
+    fn parse_tagged<V>(&mut self, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        self.recursion_checked(|de| de.parse_value(visitor))
+        match self.parse_tag()? {
+            Some(tag) => {
+                self.recursion_checked(|de| visitor.visit_enum(TagAccess { de, tag, index: 0 }))
+            }
+            None => visitor.visit_newtype_struct(self),
+        }
     }
 
-    fn recursion_checked<F, T>(&mut self, f: F) -> Result<T>
+    /// Deserializes a map key and checks that its encoding was not seen before in this map.
+    ///
+    /// In strict mode the key must also sort after the previous key.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn parse_unique_key<K>(&mut self, seed: K, keys: &mut MapKeys) -> Result<K::Value>
     where
-        F: FnOnce(&mut Deserializer<R>) -> Result<T>,
+        K: de::DeserializeSeed<'de>,
     {
-        self.remaining_depth -= 1;
-        if self.remaining_depth == 0 {
-            return Err(self.error(ErrorCode::RecursionLimitExceeded));
+        let offset = self.read.offset();
+        let (value, key) = self.recorded(|de| seed.deserialize(de))?;
+        if self.strict {
+            if let Some(ref last_key) = keys.last {
+                let order = if self.length_first_keys {
+                    last_key
+                        .len()
+                        .cmp(&key.len())
+                        .then_with(|| last_key.cmp(&key))
+                } else {
+                    last_key.cmp(&key)
+                };
+                match order {
+                    Ordering::Less => {}
+                    Ordering::Equal => return Err(Error::syntax(ErrorCode::DuplicateKey, offset)),
+                    Ordering::Greater => {
+                        return Err(Error::syntax(ErrorCode::UnsortedMapKeys, offset));
+                    }
+                }
+            }
+            keys.last = Some(key);
+        } else if !keys.seen.insert(key) {
+            return Err(Error::syntax(ErrorCode::DuplicateKey, offset));
         }
-        let r = f(self);
-        self.remaining_depth += 1;
-        r
+        Ok(value)
     }
 
-    fn parse_array<V>(&mut self, mut len: usize, visitor: V) -> Result<V::Value>
+    /// Reads the encoding of the next map key without deserializing it and returns its offset
+    /// and encoding.
+    ///
+    /// If the key was seen before in this map, the key and its value are skipped and `None` is
+    /// returned.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn parse_first_key(&mut self, keys: &mut MapKeys) -> Result<Option<(u64, Vec<u8>)>> {
+        let start = self.read.offset();
+        let (_, key): (de::IgnoredAny, _) = self.recorded(|de| de::Deserialize::deserialize(de))?;
+        if keys.seen.contains(&key) {
+            let _: de::IgnoredAny = de::Deserialize::deserialize(&mut *self)?;
+            return Ok(None);
+        }
+        keys.seen.insert(key.clone());
+        Ok(Some((start, key)))
+    }
+
+    /// Deserializes a key read by `parse_first_key`, from the input if the reader can borrow it
+    /// and from the recorded encoding otherwise.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn parse_first_key_again<K>(&mut self, seed: K, start: u64, key: &[u8]) -> Result<K::Value>
     where
-        V: de::Visitor<'de>,
+        K: de::DeserializeSeed<'de>,
     {
-        self.recursion_checked(|de| {
-            let value = visitor.visit_seq(SeqAccess { de, len: &mut len })?;
-
-            if len != 0 {
-                Err(de.error(ErrorCode::TrailingData))
-            } else {
-                Ok(value)
-            }
-        })
+        match self.read.input_since(start) {
+            Some(input) => self.parse_recorded(seed, SliceRead::new(input)),
+            None => self.parse_recorded(seed, BufferRead::new(key)),
+        }
     }
 
-    fn parse_indefinite_array<V>(&mut self, visitor: V) -> Result<V::Value>
+    /// Deserializes a value that was read from the input before.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn parse_recorded<T, R2>(&mut self, seed: T, read: R2) -> Result<T::Value>
     where
-        V: de::Visitor<'de>,
+        T: de::DeserializeSeed<'de>,
+        R2: Read<'de>,
     {
-        self.recursion_checked(|de| {
-            let value = visitor.visit_seq(IndefiniteSeqAccess { de })?;
-            match de.next()? {
-                Some(0xff) => Ok(value),
-                Some(_) => Err(de.error(ErrorCode::TrailingData)),
-                None => Err(de.error(ErrorCode::EofWhileParsingArray)),
-            }
-        })
+        let mut de = self.nested_deserializer(read);
+        let value = seed.deserialize(&mut de);
+        self.remaining_allocation = de.remaining_allocation;
+        value
+    }
+
+    /// Returns a deserializer with the same options that reads from `read`.
+    ///
+    /// The allocation budget is copied, callers must take what is left of it back afterwards.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn nested_deserializer<R2>(&self, read: R2) -> Deserializer<R2> {
+        Deserializer {
+            read,
+            remaining_depth: self.remaining_depth,
+            max_length: self.max_length,
+            max_elements: self.max_elements,
+            remaining_allocation: self.remaining_allocation,
+            accept_named: self.accept_named,
+            accept_packed: self.accept_packed,
+            accept_standard_enums: self.accept_standard_enums,
+            accept_legacy_enums: self.accept_legacy_enums,
+            strict: self.strict,
+            strip_self_describe: self.strip_self_describe,
+            value_mode: false,
+            #[cfg(all(feature = "std", feature = "bytes"))]
+            shared_bytes: false,
+            duplicate_keys: self.duplicate_keys,
+            length_first_keys: self.length_first_keys,
+            record: Vec::new(),
+            recording: 0,
+        }
+    }
+
+    /// Reads a byte string with tag 24 and passes its contents followed by the data item
+    /// decoded from it to the visitor.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn parse_embedded<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.parse_tag()? != Some(24) {
+            return Err(self.error(ErrorCode::UnexpectedTag));
+        }
+        self.recursion_checked(|de| {
+            let ByteBuf(bytes) = de::Deserialize::deserialize(&mut *de)?;
+            visitor.visit_seq(EmbeddedAccess {
+                de,
+                bytes: &bytes,
+                index: 0,
+            })
+        })
+    }
+
+    fn recursion_checked<F, T>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Deserializer<R>) -> Result<T>,
+    {
+        if self.remaining_depth <= 1 {
+            return Err(self.error(ErrorCode::RecursionLimitExceeded));
+        }
+        self.remaining_depth -= 1;
+        let r = f(self);
+        self.remaining_depth += 1;
+        r
+    }
+
+    fn parse_array<V>(&mut self, mut len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.check_elements(len)?;
+        self.recursion_checked(|de| {
+            let value = visitor.visit_seq(SeqAccess { de, len: &mut len })?;
+
+            if len != 0 {
+                Err(de.error(ErrorCode::TrailingData))
+            } else {
+                Ok(value)
+            }
+        })
+    }
+
+    fn parse_indefinite_array<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.check_indefinite()?;
+        self.recursion_checked(|de| {
+            let value = visitor.visit_seq(IndefiniteSeqAccess { de, len: 0 })?;
+            match de.next()? {
+                Some(0xff) => Ok(value),
+                Some(_) => Err(de.error(ErrorCode::TrailingData)),
+                None => Err(de.error(ErrorCode::EofWhileParsingArray)),
+            }
+        })
     }
 
     fn parse_map<V>(&mut self, mut len: usize, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        self.check_elements(len)?;
         let accept_packed = self.accept_packed;
         let accept_named = self.accept_named;
         self.recursion_checked(|de| {
@@ -476,6 +1195,8 @@ where
                 len: &mut len,
                 accept_named,
                 accept_packed,
+                #[cfg(any(feature = "std", feature = "alloc"))]
+                keys: MapKeys::default(),
             })?;
 
             if len != 0 {
@@ -490,13 +1211,17 @@ where
     where
         V: de::Visitor<'de>,
     {
+        self.check_indefinite()?;
         let accept_named = self.accept_named;
         let accept_packed = self.accept_packed;
         self.recursion_checked(|de| {
             let value = visitor.visit_map(IndefiniteMapAccess {
                 de,
+                len: 0,
                 accept_packed,
                 accept_named,
+                #[cfg(any(feature = "std", feature = "alloc"))]
+                keys: MapKeys::default(),
             })?;
             match de.next()? {
                 Some(0xff) => Ok(value),
@@ -537,6 +1262,8 @@ where
                     len: &mut len,
                     accept_packed,
                     accept_named,
+                    #[cfg(any(feature = "std", feature = "alloc"))]
+                    keys: MapKeys::default(),
                 },
             })?;
 
@@ -552,9 +1279,10 @@ where
     where
         V: de::Visitor<'de>,
     {
+        self.check_indefinite()?;
         self.recursion_checked(|de| {
             let value = visitor.visit_enum(VariantAccess {
-                seq: IndefiniteSeqAccess { de },
+                seq: IndefiniteSeqAccess { de, len: 0 },
             })?;
             match de.next()? {
                 Some(0xff) => Ok(value),
@@ -576,6 +1304,253 @@ where
         self.parse_u64().map(|i| f64::from_bits(i))
     }
 
+    /// Reads a single precision float, which in strict mode must not fit into half precision.
+    fn parse_arg_f32(&mut self) -> Result<f32> {
+        let value = self.parse_f32()?;
+        if self.strict && f32::from(f16::from_f32(value)).to_bits() == value.to_bits() {
+            return Err(self.non_canonical(ErrorCode::NonShortestFloat, 5));
+        }
+        Ok(value)
+    }
+
+    /// Reads a double precision float, which in strict mode must not fit into single precision.
+    fn parse_arg_f64(&mut self) -> Result<f64> {
+        let value = self.parse_f64()?;
+        if self.strict && f64::from(value as f32).to_bits() == value.to_bits() {
+            return Err(self.non_canonical(ErrorCode::NonShortestFloat, 9));
+        }
+        Ok(value)
+    }
+
+    /// Reads the value of a simple value encoded in two bytes, which must be at least 32.
+    fn parse_simple_u8(&mut self) -> Result<u8> {
+        let value = self.parse_u8()?;
+        if value < 0x20 {
+            return Err(self.error(ErrorCode::UnassignedCode));
+        }
+        Ok(value)
+    }
+
+    /// Reads the argument of an initial byte with the given additional information.
+    fn parse_arg(&mut self, info: u8) -> Result<u64> {
+        match info {
+            0x00..=0x17 => Ok(info.into()),
+            0x18 => self.parse_arg_u8().map(u64::from),
+            0x19 => self.parse_arg_u16().map(u64::from),
+            0x1a => self.parse_arg_u32().map(u64::from),
+            0x1b => self.parse_arg_u64(),
+            _ => Err(self.error(ErrorCode::UnassignedCode)),
+        }
+    }
+
+    fn parse_len(&self, len: u64) -> Result<usize> {
+        usize::try_from(len).map_err(|_| self.error(ErrorCode::LengthOutOfRange))
+    }
+
+    /// Reads the byte string of a bignum and returns its value.
+    ///
+    /// Returns an error if the bignum doesn't fit into an `u128`.
+    fn parse_bignum(&mut self) -> Result<u128> {
+        let byte = self.parse_u8()?;
+        let mut value = 0;
+        match byte {
+            0x40..=0x5b => {
+                let len = self.parse_arg(byte & 0x1f)?;
+                let len = self.parse_len(len)?;
+                self.parse_bignum_chunk(len, &mut value)?;
+            }
+            0x5f => {
+                self.check_indefinite()?;
+                let mut total = 0usize;
+                loop {
+                    let byte = self.parse_u8()?;
+                    match byte {
+                        0xff => break,
+                        0x40..=0x5b => {
+                            let len = self.parse_arg(byte & 0x1f)?;
+                            let len = self.parse_len(len)?;
+                            total = total.saturating_add(len);
+                            self.check_length(total)?;
+                            self.parse_bignum_chunk(len, &mut value)?;
+                        }
+                        _ => return Err(self.error(ErrorCode::UnexpectedCode)),
+                    }
+                }
+            }
+            _ => return Err(self.error(ErrorCode::UnexpectedCode)),
+        }
+        Ok(value)
+    }
+
+    fn parse_bignum_chunk(&mut self, mut len: usize, value: &mut u128) -> Result<()> {
+        self.check_length(len)?;
+        let mut buf = [0; 16];
+        while len > 0 {
+            let n = cmp::min(len, buf.len());
+            self.read_into(&mut buf[..n])?;
+            len -= n;
+            for &byte in &buf[..n] {
+                if *value >> 120 != 0 {
+                    return Err(self.error(ErrorCode::NumberOutOfRange));
+                }
+                *value = *value << 8 | u128::from(byte);
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes an integer that may be encoded as a bignum with tag 2 or 3.
+    fn parse_integer<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.peek()? {
+            Some(0xc2) => {
+                self.consume();
+                let value = self.parse_bignum()?;
+                visitor.visit_u128(value)
+            }
+            Some(0xc3) => {
+                self.consume();
+                let value = self.parse_bignum()?;
+                if value >> 127 != 0 {
+                    return Err(self.error(ErrorCode::NumberOutOfRange));
+                }
+                visitor.visit_i128(-1 - value as i128)
+            }
+            _ => self.parse_value(visitor),
+        }
+    }
+
+    /// Skips one data item without calling a visitor.
+    ///
+    /// This accepts the same input as `parse_value` when deserializing a `Value`.
+    fn skip_item(&mut self) -> Result<()> {
+        let byte = self.parse_u8()?;
+        let major = byte >> 5;
+        let info = byte & 0x1f;
+        match (major, info) {
+            (7, _) => self.skip_simple(byte),
+            (2, 0x1f) | (3, 0x1f) => self.skip_indefinite_string(major),
+            (4, 0x1f) => {
+                self.check_indefinite()?;
+                self.recursion_checked(|de| de.skip_indefinite_items(1))
+            }
+            (5, 0x1f) => {
+                self.check_indefinite()?;
+                self.recursion_checked(|de| de.skip_indefinite_items(2))
+            }
+            _ => {
+                let arg = self.parse_arg(info)?;
+                match major {
+                    0 | 1 => Ok(()),
+                    2 | 3 => {
+                        let len = self.parse_len(arg)?;
+                        self.check_length(len)?;
+                        self.skip_string(major, len)
+                    }
+                    4 | 5 => {
+                        let len = self.parse_len(arg)?;
+                        self.check_elements(len)?;
+                        let items = if major == 4 {
+                            arg
+                        } else {
+                            arg.saturating_mul(2)
+                        };
+                        self.recursion_checked(|de| {
+                            for _ in 0..items {
+                                de.skip_item()?;
+                            }
+                            Ok(())
+                        })
+                    }
+                    _ => self.recursion_checked(|de| de.skip_item()),
+                }
+            }
+        }
+    }
+
+    fn skip_simple(&mut self, byte: u8) -> Result<()> {
+        match byte {
+            0xe0..=0xf7 => Ok(()),
+            0xf8 => self.parse_simple_u8().map(|_| ()),
+            0xf9 => self.parse_f16().map(|_| ()),
+            0xfa => self.parse_arg_f32().map(|_| ()),
+            0xfb => self.parse_arg_f64().map(|_| ()),
+            0xff => Err(self.error(ErrorCode::UnexpectedCode)),
+            _ => Err(self.error(ErrorCode::UnassignedCode)),
+        }
+    }
+
+    /// Skips the elements of an indefinite-length array or map and its break code.
+    fn skip_indefinite_items(&mut self, items_per_element: u8) -> Result<()> {
+        let mut len = 0;
+        loop {
+            match self.peek()? {
+                Some(0xff) => {
+                    self.consume();
+                    return Ok(());
+                }
+                Some(_) => {}
+                None if items_per_element == 1 => {
+                    return Err(self.error(ErrorCode::EofWhileParsingArray))
+                }
+                None => return Err(self.error(ErrorCode::EofWhileParsingMap)),
+            }
+            len += 1;
+            self.check_elements(len)?;
+            for _ in 0..items_per_element {
+                self.skip_item()?;
+            }
+        }
+    }
+
+    fn skip_indefinite_string(&mut self, major: u8) -> Result<()> {
+        self.check_indefinite()?;
+        let mut total = 0usize;
+        loop {
+            let byte = self.parse_u8()?;
+            if byte == 0xff {
+                return Ok(());
+            }
+            if byte >> 5 != major || byte & 0x1f > 0x1b {
+                return Err(self.error(ErrorCode::UnexpectedCode));
+            }
+            let arg = self.parse_arg(byte & 0x1f)?;
+            let len = self.parse_len(arg)?;
+            total = total.saturating_add(len);
+            self.check_length(total)?;
+            self.skip_string(major, len)?;
+        }
+    }
+
+    /// Skips the contents of a byte or text string through a small buffer on the stack.
+    ///
+    /// Text strings are checked to be valid UTF-8.
+    fn skip_string(&mut self, major: u8, mut len: usize) -> Result<()> {
+        let mut buf = [0; 256];
+        // Bytes of an incomplete UTF-8 sequence at the start of `buf`.
+        let mut pending = 0;
+        while len > 0 {
+            let n = cmp::min(len, buf.len() - pending);
+            self.read_into(&mut buf[pending..pending + n])?;
+            len -= n;
+            let filled = pending + n;
+            pending = 0;
+            if major == 3 {
+                if let Err(e) = str::from_utf8(&buf[..filled]) {
+                    if e.error_len().is_some() || len == 0 {
+                        let offset = self.read.offset() - (filled - e.valid_up_to()) as u64;
+                        return Err(Error::syntax(ErrorCode::InvalidUtf8, offset));
+                    }
+                    buf.copy_within(e.valid_up_to()..filled, 0);
+                    pending = filled - e.valid_up_to();
+                }
+            }
+        }
+        Ok(())
+    }
+
     // Don't warn about the `unreachable!` in case
     // exhaustive integer pattern matching is enabled.
     #[allow(unreachable_patterns)]
@@ -588,19 +1563,19 @@ where
             // Major type 0: an unsigned integer
             0x00..=0x17 => visitor.visit_u8(byte),
             0x18 => {
-                let value = self.parse_u8()?;
+                let value = self.parse_arg_u8()?;
                 visitor.visit_u8(value)
             }
             0x19 => {
-                let value = self.parse_u16()?;
+                let value = self.parse_arg_u16()?;
                 visitor.visit_u16(value)
             }
             0x1a => {
-                let value = self.parse_u32()?;
+                let value = self.parse_arg_u32()?;
                 visitor.visit_u32(value)
             }
             0x1b => {
-                let value = self.parse_u64()?;
+                let value = self.parse_arg_u64()?;
                 visitor.visit_u64(value)
             }
             0x1c..=0x1f => Err(self.error(ErrorCode::UnassignedCode)),
@@ -608,19 +1583,19 @@ where
             // Major type 1: a negative integer
             0x20..=0x37 => visitor.visit_i8(-1 - (byte - 0x20) as i8),
             0x38 => {
-                let value = self.parse_u8()?;
+                let value = self.parse_arg_u8()?;
                 visitor.visit_i16(-1 - i16::from(value))
             }
             0x39 => {
-                let value = self.parse_u16()?;
+                let value = self.parse_arg_u16()?;
                 visitor.visit_i32(-1 - i32::from(value))
             }
             0x3a => {
-                let value = self.parse_u32()?;
+                let value = self.parse_arg_u32()?;
                 visitor.visit_i64(-1 - i64::from(value))
             }
             0x3b => {
-                let value = self.parse_u64()?;
+                let value = self.parse_arg_u64()?;
                 if value > i64::max_value() as u64 {
                     return visitor.visit_i128(-1 - i128::from(value));
                 }
@@ -631,19 +1606,19 @@ where
             // Major type 2: a byte string
             0x40..=0x57 => self.parse_bytes(byte as usize - 0x40, visitor),
             0x58 => {
-                let len = self.parse_u8()?;
+                let len = self.parse_arg_u8()?;
                 self.parse_bytes(len as usize, visitor)
             }
             0x59 => {
-                let len = self.parse_u16()?;
+                let len = self.parse_arg_u16()?;
                 self.parse_bytes(len as usize, visitor)
             }
             0x5a => {
-                let len = self.parse_u32()?;
+                let len = self.parse_arg_u32()?;
                 self.parse_bytes(len as usize, visitor)
             }
             0x5b => {
-                let len = self.parse_u64()?;
+                let len = self.parse_arg_u64()?;
                 if len > usize::max_value() as u64 {
                     return Err(self.error(ErrorCode::LengthOutOfRange));
                 }
@@ -655,19 +1630,19 @@ where
             // Major type 3: a text string
             0x60..=0x77 => self.parse_str(byte as usize - 0x60, visitor),
             0x78 => {
-                let len = self.parse_u8()?;
+                let len = self.parse_arg_u8()?;
                 self.parse_str(len as usize, visitor)
             }
             0x79 => {
-                let len = self.parse_u16()?;
+                let len = self.parse_arg_u16()?;
                 self.parse_str(len as usize, visitor)
             }
             0x7a => {
-                let len = self.parse_u32()?;
+                let len = self.parse_arg_u32()?;
                 self.parse_str(len as usize, visitor)
             }
             0x7b => {
-                let len = self.parse_u64()?;
+                let len = self.parse_arg_u64()?;
                 if len > usize::max_value() as u64 {
                     return Err(self.error(ErrorCode::LengthOutOfRange));
                 }
@@ -679,19 +1654,19 @@ where
             // Major type 4: an array of data items
             0x80..=0x97 => self.parse_array(byte as usize - 0x80, visitor),
             0x98 => {
-                let len = self.parse_u8()?;
+                let len = self.parse_arg_u8()?;
                 self.parse_array(len as usize, visitor)
             }
             0x99 => {
-                let len = self.parse_u16()?;
+                let len = self.parse_arg_u16()?;
                 self.parse_array(len as usize, visitor)
             }
             0x9a => {
-                let len = self.parse_u32()?;
+                let len = self.parse_arg_u32()?;
                 self.parse_array(len as usize, visitor)
             }
             0x9b => {
-                let len = self.parse_u64()?;
+                let len = self.parse_arg_u64()?;
                 if len > usize::max_value() as u64 {
                     return Err(self.error(ErrorCode::LengthOutOfRange));
                 }
@@ -703,19 +1678,19 @@ where
             // Major type 5: a map of pairs of data items
             0xa0..=0xb7 => self.parse_map(byte as usize - 0xa0, visitor),
             0xb8 => {
-                let len = self.parse_u8()?;
+                let len = self.parse_arg_u8()?;
                 self.parse_map(len as usize, visitor)
             }
             0xb9 => {
-                let len = self.parse_u16()?;
+                let len = self.parse_arg_u16()?;
                 self.parse_map(len as usize, visitor)
             }
             0xba => {
-                let len = self.parse_u32()?;
+                let len = self.parse_arg_u32()?;
                 self.parse_map(len as usize, visitor)
             }
             0xbb => {
-                let len = self.parse_u64()?;
+                let len = self.parse_arg_u64()?;
                 if len > usize::max_value() as u64 {
                     return Err(self.error(ErrorCode::LengthOutOfRange));
                 }
@@ -725,45 +1700,58 @@ where
             0xbf => self.parse_indefinite_map(visitor),
 
             // Major type 6: optional semantic tagging of other major types
+            0xc2 | 0xc3 if self.value_mode => match self.peek()? {
+                Some(0x40..=0x5b) | Some(0x5f) => visitor.visit_enum(BignumAccess {
+                    de: self,
+                    tag: byte - 0xc0,
+                }),
+                _ => self.handle_tagged_value(u64::from(byte) - 0xc0, visitor),
+            },
             0xc0..=0xd7 => {
                 let tag = u64::from(byte) - 0xc0;
                 self.handle_tagged_value(tag, visitor)
             }
             0xd8 => {
-                let tag = self.parse_u8()?;
+                let tag = self.parse_arg_u8()?;
                 self.handle_tagged_value(tag.into(), visitor)
             }
             0xd9 => {
-                let tag = self.parse_u16()?;
+                let tag = self.parse_arg_u16()?;
                 self.handle_tagged_value(tag.into(), visitor)
             }
             0xda => {
-                let tag = self.parse_u32()?;
+                let tag = self.parse_arg_u32()?;
                 self.handle_tagged_value(tag.into(), visitor)
             }
             0xdb => {
-                let tag = self.parse_u64()?;
+                let tag = self.parse_arg_u64()?;
                 self.handle_tagged_value(tag, visitor)
             }
             0xdc..=0xdf => Err(self.error(ErrorCode::UnassignedCode)),
 
             // Major type 7: floating-point numbers and other simple data types that need no content
+            0xe0..=0xf3 if self.value_mode => visitor.visit_enum(SimpleValueAccess(byte - 0xe0)),
             0xe0..=0xf3 => Err(self.error(ErrorCode::UnassignedCode)),
             0xf4 => visitor.visit_bool(false),
             0xf5 => visitor.visit_bool(true),
             0xf6 => visitor.visit_unit(),
+            0xf7 if self.value_mode => visitor.visit_enum(SimpleValueAccess(23)),
             0xf7 => visitor.visit_unit(),
+            0xf8 if self.value_mode => {
+                let value = self.parse_simple_u8()?;
+                visitor.visit_enum(SimpleValueAccess(value))
+            }
             0xf8 => Err(self.error(ErrorCode::UnassignedCode)),
             0xf9 => {
                 let value = self.parse_f16()?;
                 visitor.visit_f32(value)
             }
             0xfa => {
-                let value = self.parse_f32()?;
+                let value = self.parse_arg_f32()?;
                 visitor.visit_f32(value)
             }
             0xfb => {
-                let value = self.parse_f64()?;
+                let value = self.parse_arg_f64()?;
                 visitor.visit_f64(value)
             }
             0xfc..=0xfe => Err(self.error(ErrorCode::UnassignedCode)),
@@ -803,10 +1791,45 @@ where
     }
 
     #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &str, visitor: V) -> Result<V::Value>
+    #[cfg_attr(not(any(feature = "std", feature = "alloc")), allow(unused_variables))]
+    fn deserialize_newtype_struct<V>(self, name: &str, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if name == RAW_VALUE_NAME {
+                let ((), bytes) = self.recorded(|de| de.skip_item())?;
+                return visitor.visit_byte_buf(bytes);
+            }
+        }
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if name == CBOR_EMBEDDED_NAME {
+                return self.parse_embedded(visitor);
+            }
+        }
+        #[cfg(feature = "tags")]
+        {
+            if name == CBOR_NEWTYPE_NAME {
+                return self.parse_tagged(visitor);
+            }
+        }
+        #[cfg(all(feature = "std", feature = "bytes"))]
+        {
+            if name == SHARED_BYTES_NAME {
+                let shared_bytes = mem::replace(&mut self.shared_bytes, true);
+                let r = de::Deserializer::deserialize_byte_buf(&mut *self, visitor);
+                self.shared_bytes = shared_bytes;
+                return r;
+            }
+        }
+        if name == CBOR_VALUE_NAME {
+            let value_mode = mem::replace(&mut self.value_mode, true);
+            let r = self.parse_value(visitor);
+            self.value_mode = value_mode;
+            return r;
+        }
         visitor.visit_newtype_struct(self)
     }
 
@@ -832,19 +1855,19 @@ where
                 match byte {
                     0x80..=0x97 => self.parse_enum(byte as usize - 0x80, visitor),
                     0x98 => {
-                        let len = self.parse_u8()?;
+                        let len = self.parse_arg_u8()?;
                         self.parse_enum(len as usize, visitor)
                     }
                     0x99 => {
-                        let len = self.parse_u16()?;
+                        let len = self.parse_arg_u16()?;
                         self.parse_enum(len as usize, visitor)
                     }
                     0x9a => {
-                        let len = self.parse_u32()?;
+                        let len = self.parse_arg_u32()?;
                         self.parse_enum(len as usize, visitor)
                     }
                     0x9b => {
-                        let len = self.parse_u64()?;
+                        let len = self.parse_arg_u64()?;
                         if len > usize::max_value() as u64 {
                             return Err(self.error(ErrorCode::LengthOutOfRange));
                         }
@@ -878,8 +1901,22 @@ where
         false
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.parse_integer(visitor)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.parse_integer(visitor)
+    }
+
     serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string unit
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string unit
         unit_struct seq tuple tuple_struct map struct identifier ignored_any
         bytes byte_buf
     }
@@ -940,6 +1977,7 @@ where
 
 struct IndefiniteSeqAccess<'a, R> {
     de: &'a mut Deserializer<R>,
+    len: usize,
 }
 
 impl<'de, 'a, R> de::SeqAccess<'de> for IndefiniteSeqAccess<'a, R>
@@ -958,6 +1996,8 @@ where
             None => return Err(self.de.error(ErrorCode::EofWhileParsingArray)),
         }
 
+        self.len += 1;
+        self.de.check_elements(self.len)?;
         let value = seed.deserialize(&mut *self.de)?;
         Ok(Some(value))
     }
@@ -977,6 +2017,8 @@ struct MapAccess<'a, R> {
     len: &'a mut usize,
     accept_named: bool,
     accept_packed: bool,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    keys: MapKeys,
 }
 
 impl<'de, 'a, R> de::MapAccess<'de> for MapAccess<'a, R>
@@ -989,23 +2031,39 @@ where
     where
         K: de::DeserializeSeed<'de>,
     {
-        if *self.len == 0 {
-            return Ok(None);
-        }
-        *self.len -= 1;
-
-        match self.de.peek()? {
-            Some(_byte @ 0x00..=0x1b) if !self.accept_packed => {
-                return Err(self.de.error(ErrorCode::WrongStructFormat));
-            }
-            Some(_byte @ 0x60..=0x7f) if !self.accept_named => {
-                return Err(self.de.error(ErrorCode::WrongStructFormat));
+        loop {
+            if *self.len == 0 {
+                return Ok(None);
             }
-            _ => {}
-        };
+            *self.len -= 1;
 
-        let value = seed.deserialize(&mut *self.de)?;
-        Ok(Some(value))
+            match self.de.peek()? {
+                Some(_byte @ 0x00..=0x1b) if !self.accept_packed => {
+                    return Err(self.de.error(ErrorCode::WrongStructFormat));
+                }
+                Some(_byte @ 0x60..=0x7f) if !self.accept_named => {
+                    return Err(self.de.error(ErrorCode::WrongStructFormat));
+                }
+                _ => {}
+            };
+
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            {
+                if self.de.strict || self.de.duplicate_keys == DuplicateKeys::Error {
+                    return self.de.parse_unique_key(seed, &mut self.keys).map(Some);
+                }
+                if self.de.duplicate_keys == DuplicateKeys::FirstWins {
+                    match self.de.parse_first_key(&mut self.keys)? {
+                        Some((start, key)) => {
+                            return self.de.parse_first_key_again(seed, start, &key).map(Some);
+                        }
+                        None => continue,
+                    }
+                }
+            }
+            let value = seed.deserialize(&mut *self.de)?;
+            return Ok(Some(value));
+        }
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
@@ -1031,8 +2089,11 @@ where
 
 struct IndefiniteMapAccess<'a, R> {
     de: &'a mut Deserializer<R>,
+    len: usize,
     accept_packed: bool,
     accept_named: bool,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    keys: MapKeys,
 }
 
 impl<'de, 'a, R> de::MapAccess<'de> for IndefiniteMapAccess<'a, R>
@@ -1045,20 +2106,38 @@ where
     where
         K: de::DeserializeSeed<'de>,
     {
-        match self.de.peek()? {
-            Some(_byte @ 0x00..=0x1b) if !self.accept_packed => {
-                return Err(self.de.error(ErrorCode::WrongStructFormat))
+        loop {
+            match self.de.peek()? {
+                Some(_byte @ 0x00..=0x1b) if !self.accept_packed => {
+                    return Err(self.de.error(ErrorCode::WrongStructFormat))
+                }
+                Some(_byte @ 0x60..=0x7f) if !self.accept_named => {
+                    return Err(self.de.error(ErrorCode::WrongStructFormat))
+                }
+                Some(0xff) => return Ok(None),
+                Some(_) => {}
+                None => return Err(self.de.error(ErrorCode::EofWhileParsingMap)),
             }
-            Some(_byte @ 0x60..=0x7f) if !self.accept_named => {
-                return Err(self.de.error(ErrorCode::WrongStructFormat))
+
+            self.len += 1;
+            self.de.check_elements(self.len)?;
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            {
+                if self.de.duplicate_keys == DuplicateKeys::Error {
+                    return self.de.parse_unique_key(seed, &mut self.keys).map(Some);
+                }
+                if self.de.duplicate_keys == DuplicateKeys::FirstWins {
+                    match self.de.parse_first_key(&mut self.keys)? {
+                        Some((start, key)) => {
+                            return self.de.parse_first_key_again(seed, start, &key).map(Some);
+                        }
+                        None => continue,
+                    }
+                }
             }
-            Some(0xff) => return Ok(None),
-            Some(_) => {}
-            None => return Err(self.de.error(ErrorCode::EofWhileParsingMap)),
+            let value = seed.deserialize(&mut *self.de)?;
+            return Ok(Some(value));
         }
-
-        let value = seed.deserialize(&mut *self.de)?;
-        Ok(Some(value))
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
@@ -1069,6 +2148,296 @@ where
     }
 }
 
+/// The encoded keys of a map that were read so far.
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Default)]
+struct MapKeys {
+    /// The previous key, used to check the key order in strict mode.
+    last: Option<Vec<u8>>,
+    /// All keys, used to detect duplicates in lenient mode.
+    seen: BTreeSet<Vec<u8>>,
+}
+
+/// Passes a simple value to the visitor of a `Value` as a unit variant whose identifier is the
+/// number of the simple value as an `u16`.
+struct SimpleValueAccess(u8);
+
+impl<'de> de::EnumAccess<'de> for SimpleValueAccess {
+    type Error = Error;
+    type Variant = SimpleValueAccess;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, SimpleValueAccess)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let deserializer: de::value::U16Deserializer<Error> = u16::from(self.0).into_deserializer();
+        let variant = seed.deserialize(deserializer)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for SimpleValueAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"newtype variant",
+        ))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+/// Passes the contents of an embedded data item and the decoded item to a visitor.
+#[cfg(any(feature = "std", feature = "alloc"))]
+struct EmbeddedAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+    bytes: &'a [u8],
+    index: usize,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'de, 'a, R> de::SeqAccess<'de> for EmbeddedAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.index += 1;
+        match self.index {
+            1 => seed.deserialize(BytesDeserializer(self.bytes)).map(Some),
+            2 => {
+                let mut de = self.de.nested_deserializer(BufferRead::new(self.bytes));
+                let value = seed.deserialize(&mut de);
+                self.de.remaining_allocation = de.remaining_allocation;
+                let value = value?;
+                de.end()?;
+                Ok(Some(value))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(2usize.saturating_sub(self.index))
+    }
+}
+
+/// A deserializer for a byte string.
+#[cfg(any(feature = "std", feature = "alloc"))]
+struct BytesDeserializer<'a>(&'a [u8]);
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'de, 'a> de::Deserializer<'de> for BytesDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_bytes(self.0)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+        bytes byte_buf option newtype_struct enum
+    }
+}
+
+/// Passes a bignum to the visitor of a `Value` as a newtype variant containing its byte string.
+///
+/// The identifier is the tag number plus 256, so that it can't be confused with a simple value.
+struct BignumAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+    tag: u8,
+}
+
+impl<'de, 'a, R> de::EnumAccess<'de> for BignumAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+    type Variant = BignumAccess<'a, R>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, BignumAccess<'a, R>)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let deserializer: de::value::U16Deserializer<Error> =
+            (0x100 | u16::from(self.tag)).into_deserializer();
+        let variant = seed.deserialize(deserializer)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a, R> de::VariantAccess<'de> for BignumAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"unit variant",
+        ))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+/// Passes a tagged value as a tuple variant of the tag and the value.
+#[cfg(feature = "tags")]
+struct TagAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+    tag: u64,
+    index: usize,
+}
+
+#[cfg(feature = "tags")]
+impl<'de, 'a, R> de::EnumAccess<'de> for TagAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+    type Variant = TagAccess<'a, R>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, TagAccess<'a, R>)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let deserializer: de::value::StrDeserializer<'_, Error> =
+            CBOR_NEWTYPE_NAME.into_deserializer();
+        let variant = seed.deserialize(deserializer)?;
+        Ok((variant, self))
+    }
+}
+
+#[cfg(feature = "tags")]
+impl<'de, 'a, R> de::VariantAccess<'de> for TagAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(de::Error::invalid_type(
+            de::Unexpected::TupleVariant,
+            &"unit variant",
+        ))
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::TupleVariant,
+            &"newtype variant",
+        ))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(self)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::TupleVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+#[cfg(feature = "tags")]
+impl<'de, 'a, R> de::SeqAccess<'de> for TagAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.index += 1;
+        match self.index {
+            1 => {
+                let deserializer: de::value::U64Deserializer<Error> = self.tag.into_deserializer();
+                seed.deserialize(deserializer).map(Some)
+            }
+            2 => seed.deserialize(&mut *self.de).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(2usize.saturating_sub(self.index))
+    }
+}
+
 struct UnitVariantAccess<'a, R> {
     de: &'a mut Deserializer<R>,
 }
@@ -0,0 +1,265 @@
+//! Decimal fractions and bigfloats.
+//!
+//! [RFC 8949](https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.4) defines tag 4 for
+//! decimal fractions `m * 10^e` and tag 5 for bigfloats `m * 2^e`. Both are encoded as a
+//! two-element array of the exponent and the mantissa. The exponent is an integer and the
+//! mantissa an integer or bignum.
+//!
+//! ```rust
+//! use serde_cbor::decimal::Decimal;
+//!
+//! let price: Decimal = "273.15".parse().unwrap();
+//! assert_eq!(price.exponent, -2);
+//! // 4([-2, 27315])
+//! let bytes = serde_cbor::to_vec(&price).unwrap();
+//! assert_eq!(bytes, b"\xc4\x82\x21\x19\x6a\xb3");
+//! let price: Decimal = serde_cbor::from_slice(&bytes).unwrap();
+//! assert_eq!(price.to_string(), "273.15");
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use crate::error::{Error, ErrorCode};
+use crate::tags::{unexpected_tag, Tagged};
+use crate::value::bigint::{mul_add, to_decimal};
+use crate::value::BigInt;
+
+/// A decimal fraction with the value `mantissa * 10^exponent`, encoded with tag 4.
+///
+/// Decimal fractions are converted to and from strings like `-1.25` or `15e3` without loss of
+/// precision. The number of fractional digits is kept, so `"1.50"` has the mantissa 150 and the
+/// exponent -2.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    /// The power of ten the mantissa is multiplied with.
+    pub exponent: i64,
+    /// The digits of the number.
+    pub mantissa: BigInt,
+}
+
+impl Decimal {
+    /// Creates a decimal fraction with the value `mantissa * 10^exponent`.
+    pub fn new<M: Into<BigInt>>(exponent: i64, mantissa: M) -> Decimal {
+        Decimal {
+            exponent,
+            mantissa: mantissa.into(),
+        }
+    }
+}
+
+/// A bigfloat with the value `mantissa * 2^exponent`, encoded with tag 5.
+///
+/// Bigfloats are converted to and from hexadecimal floating point strings like `0x3p-1` or
+/// `-0x1.8p3` without loss of precision.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigFloat {
+    /// The power of two the mantissa is multiplied with.
+    pub exponent: i64,
+    /// The digits of the number.
+    pub mantissa: BigInt,
+}
+
+impl BigFloat {
+    /// Creates a bigfloat with the value `mantissa * 2^exponent`.
+    pub fn new<M: Into<BigInt>>(exponent: i64, mantissa: M) -> BigFloat {
+        BigFloat {
+            exponent,
+            mantissa: mantissa.into(),
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mantissa.is_negative() {
+            f.write_str("-")?;
+        }
+        let digits = to_decimal(self.mantissa.abs());
+        // Write small negative exponents as fractions and everything else in exponent notation.
+        let scale = -i128::from(self.exponent);
+        if scale < 0 || scale > digits.len() as i128 + 6 {
+            write!(f, "{}e{}", digits, self.exponent)
+        } else if scale == 0 {
+            f.write_str(&digits)
+        } else if scale < digits.len() as i128 {
+            let (int, frac) = digits.split_at(digits.len() - scale as usize);
+            write!(f, "{}.{}", int, frac)
+        } else {
+            f.write_str("0.")?;
+            for _ in digits.len()..scale as usize {
+                f.write_str("0")?;
+            }
+            f.write_str(&digits)
+        }
+    }
+}
+
+impl fmt::Display for BigFloat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mantissa.is_negative() {
+            f.write_str("-")?;
+        }
+        let abs = self.mantissa.abs();
+        match abs.split_first() {
+            Some((first, rest)) => {
+                write!(f, "0x{:x}", first)?;
+                for byte in rest {
+                    write!(f, "{:02x}", byte)?;
+                }
+            }
+            None => f.write_str("0x0")?,
+        }
+        write!(f, "p{}", self.exponent)
+    }
+}
+
+/// A number split into its sign, mantissa, fractional digits and exponent.
+struct Parts {
+    negative: bool,
+    abs: Vec<u8>,
+    frac_digits: i64,
+    exponent: i64,
+}
+
+/// Parses the digits with the given radix, an optional fraction and the exponent following the
+/// given marker character.
+fn parse_parts(s: &str, start: usize, radix: u8, marker: u8) -> Result<Parts, Error> {
+    let bytes = s.as_bytes();
+    let negative = bytes.first() == Some(&b'-');
+    let mut pos = start;
+    let mut abs = Vec::new();
+    let mut digits = 0;
+    let mut frac_digits = None;
+    let mut exponent = 0;
+    let error = |pos: usize| Error::syntax(ErrorCode::InvalidNumber, pos as u64);
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if let Some(d) = (c as char).to_digit(u32::from(radix)) {
+            mul_add(&mut abs, radix, d as u8);
+            digits += 1;
+            frac_digits = frac_digits.map(|n: i64| n + 1);
+        } else if c == b'.' && frac_digits.is_none() {
+            frac_digits = Some(0);
+        } else if c.to_ascii_lowercase() == marker && digits > 0 {
+            exponent = s[pos + 1..].parse().map_err(|_| error(pos + 1))?;
+            pos = bytes.len();
+            break;
+        } else {
+            return Err(error(pos));
+        }
+        pos += 1;
+    }
+    if digits == 0 {
+        return Err(error(pos));
+    }
+    Ok(Parts {
+        negative,
+        abs,
+        frac_digits: frac_digits.unwrap_or(0),
+        exponent,
+    })
+}
+
+fn sign_len(s: &str) -> usize {
+    match s.as_bytes().first() {
+        Some(b'-') | Some(b'+') => 1,
+        _ => 0,
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = Error;
+
+    /// Parses a decimal number with an optional sign, fraction and exponent.
+    fn from_str(s: &str) -> Result<Decimal, Error> {
+        let parts = parse_parts(s, sign_len(s), 10, b'e')?;
+        let exponent = parts
+            .exponent
+            .checked_sub(parts.frac_digits)
+            .ok_or_else(|| Error::syntax(ErrorCode::InvalidNumber, s.len() as u64))?;
+        Ok(Decimal {
+            exponent,
+            mantissa: BigInt::from_abs(parts.negative, parts.abs),
+        })
+    }
+}
+
+impl FromStr for BigFloat {
+    type Err = Error;
+
+    /// Parses a hexadecimal floating point number with an optional sign, fraction and binary
+    /// exponent, like `-0x1.8p3`.
+    fn from_str(s: &str) -> Result<BigFloat, Error> {
+        let start = sign_len(s);
+        match s.get(start..start + 2) {
+            Some("0x") | Some("0X") => {}
+            _ => return Err(Error::syntax(ErrorCode::InvalidNumber, start as u64)),
+        }
+        let parts = parse_parts(s, start + 2, 16, b'p')?;
+        let exponent = parts
+            .frac_digits
+            .checked_mul(4)
+            .and_then(|shift| parts.exponent.checked_sub(shift))
+            .ok_or_else(|| Error::syntax(ErrorCode::InvalidNumber, s.len() as u64))?;
+        Ok(BigFloat {
+            exponent,
+            mantissa: BigInt::from_abs(parts.negative, parts.abs),
+        })
+    }
+}
+
+fn serialize_fraction<S>(
+    tag: u64,
+    exponent: i64,
+    mantissa: &BigInt,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    Tagged::new(Some(tag), (exponent, mantissa)).serialize(s)
+}
+
+/// Deserializes the exponent and mantissa of a fraction.
+///
+/// Untagged arrays are accepted, so that the types can also be read from formats without tags.
+fn deserialize_fraction<'de, D>(tag: u64, d: D) -> Result<(i64, BigInt), D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tagged = Tagged::<(i64, BigInt)>::deserialize(d)?;
+    match tagged.tag {
+        Some(t) if t != tag => Err(unexpected_tag(tagged.tag, tag)),
+        _ => Ok(tagged.value),
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_fraction(4, self.exponent, &self.mantissa, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Decimal, D::Error> {
+        let (exponent, mantissa) = deserialize_fraction(4, d)?;
+        Ok(Decimal { exponent, mantissa })
+    }
+}
+
+impl Serialize for BigFloat {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_fraction(5, self.exponent, &self.mantissa, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigFloat {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<BigFloat, D::Error> {
+        let (exponent, mantissa) = deserialize_fraction(5, d)?;
+        Ok(BigFloat { exponent, mantissa })
+    }
+}
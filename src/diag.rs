@@ -0,0 +1,919 @@
+//! CBOR diagnostic notation.
+//!
+//! Diagnostic notation, described in [RFC 8949, section 8], is a human-readable text format for
+//! CBOR data items based on JSON. It is meant for logs, debugging and tests, not for exchanging
+//! data. `to_string` renders CBOR data in diagnostic notation and `parse` turns diagnostic
+//! notation back into CBOR data. Unlike the `Debug` output of `Value` diagnostic notation shows
+//! tags, indefinite lengths and the sizes of arguments that are not encoded in their shortest
+//! form:
+//!
+//! * Byte strings are written as `h'0102'` and text strings as `"text"`.
+//! * Tagged items are written as `1(1363896240)`.
+//! * Indefinite-length items start with an underscore, like `[_ 1, 2]` or `(_ "a", "b")`.
+//! * An argument that could have been encoded in fewer bytes gets an encoding indicator: `_0`,
+//!   `_1`, `_2` and `_3` mean that it was encoded in 1, 2, 4 or 8 bytes, as in `1_1` or `[_0 ]`.
+//!   For floats `_1`, `_2` and `_3` mean half, single and double precision.
+//! * Simple values without a name are written as `simple(16)`.
+//! * Floats are written with a fraction or exponent, or as `NaN`, `Infinity` and `-Infinity`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde_cbor::diag;
+//!
+//! let bytes = b"\x9f\xc1\x1a\x51\x4b\x67\xb0\x42\x01\x02\x19\x00\x01\xf9\x7c\x00\xff";
+//! let diag = diag::to_string(bytes).unwrap();
+//! assert_eq!(diag, "[_ 1(1363896240), h'0102', 1_1, Infinity]");
+//! ```
+//!
+//! [RFC 8949, section 8]: https://www.rfc-editor.org/rfc/rfc8949.html#section-8
+
+#[cfg(feature = "alloc")]
+use alloc::format;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+use core::str;
+use half::f16;
+
+use crate::error::{Error, ErrorCode, Result};
+#[cfg(feature = "std")]
+use crate::value::Value;
+
+/// Renders a single CBOR data item in diagnostic notation.
+///
+/// Returns an error if `bytes` isn't exactly one well-formed data item or contains a text
+/// string that is not valid UTF-8.
+pub fn to_string(bytes: &[u8]) -> Result<String> {
+    let mut printer = Printer {
+        bytes,
+        index: 0,
+        out: String::new(),
+        remaining_depth: 128,
+    };
+    printer.item()?;
+    if printer.index != bytes.len() {
+        return Err(Error::syntax(
+            ErrorCode::TrailingData,
+            printer.index as u64 + 1,
+        ));
+    }
+    Ok(printer.out)
+}
+
+/// Renders a `Value` in diagnostic notation.
+///
+/// The value is rendered as it would be serialized.
+#[cfg(feature = "std")]
+pub fn value_to_string(value: &Value) -> Result<String> {
+    to_string(&crate::to_vec(value)?)
+}
+
+struct Printer<'a> {
+    bytes: &'a [u8],
+    index: usize,
+    out: String,
+    remaining_depth: u8,
+}
+
+impl<'a> Printer<'a> {
+    fn error(&self, code: ErrorCode) -> Error {
+        Error::syntax(code, self.index as u64)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.index).cloned()
+    }
+
+    fn next(&mut self) -> Result<u8> {
+        let byte = self.take(1)?;
+        Ok(byte[0])
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        match self.index.checked_add(len) {
+            Some(end) if end <= self.bytes.len() => {
+                let slice = &self.bytes[self.index..end];
+                self.index = end;
+                Ok(slice)
+            }
+            _ => Err(Error::syntax(
+                ErrorCode::EofWhileParsingValue,
+                self.bytes.len() as u64,
+            )),
+        }
+    }
+
+    fn take_array<T: Default + AsMut<[u8]>>(&mut self) -> Result<T> {
+        let mut buf = T::default();
+        let len = buf.as_mut().len();
+        buf.as_mut().copy_from_slice(self.take(len)?);
+        Ok(buf)
+    }
+
+    /// Reads the argument for the given additional information and its encoding indicator.
+    fn argument(&mut self, info: u8) -> Result<(u64, &'static str)> {
+        match info {
+            0x00..=0x17 => Ok((info.into(), "")),
+            0x18 => {
+                let value = self.next()?;
+                Ok((value.into(), if value < 0x18 { "_0" } else { "" }))
+            }
+            0x19 => {
+                let value = u16::from_be_bytes(self.take_array()?);
+                Ok((value.into(), if value <= 0xff { "_1" } else { "" }))
+            }
+            0x1a => {
+                let value = u32::from_be_bytes(self.take_array()?);
+                Ok((value.into(), if value <= 0xffff { "_2" } else { "" }))
+            }
+            0x1b => {
+                let value = u64::from_be_bytes(self.take_array()?);
+                Ok((value, if value <= 0xffff_ffff { "_3" } else { "" }))
+            }
+            _ => Err(self.error(ErrorCode::UnassignedCode)),
+        }
+    }
+
+    fn length(&self, len: u64) -> Result<usize> {
+        usize::try_from(len).map_err(|_| self.error(ErrorCode::LengthOutOfRange))
+    }
+
+    fn nested<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.remaining_depth -= 1;
+        if self.remaining_depth == 0 {
+            return Err(self.error(ErrorCode::RecursionLimitExceeded));
+        }
+        let r = f(self);
+        self.remaining_depth += 1;
+        r
+    }
+
+    fn item(&mut self) -> Result<()> {
+        let byte = self.next()?;
+        let major = byte >> 5;
+        let info = byte & 0x1f;
+        match (major, info) {
+            (7, _) => return self.simple(info),
+            (2, 0x1f) | (3, 0x1f) => return self.indefinite_string(major),
+            (4, 0x1f) => return self.nested(|p| p.indefinite_items("[_ ", "]", false)),
+            (5, 0x1f) => return self.nested(|p| p.indefinite_items("{_ ", "}", true)),
+            _ => {}
+        }
+
+        let (arg, indicator) = self.argument(info)?;
+        match major {
+            0 => self.out.push_str(&arg.to_string()),
+            1 => self.out.push_str(&(-1 - i128::from(arg)).to_string()),
+            2 | 3 => return self.string(major, arg, indicator),
+            4 | 5 => {
+                let map = major == 5;
+                return self.nested(|p| {
+                    p.out.push(if map { '{' } else { '[' });
+                    if !indicator.is_empty() {
+                        p.out.push_str(indicator);
+                        p.out.push(' ');
+                    }
+                    for i in 0..arg {
+                        if i > 0 {
+                            p.out.push_str(", ");
+                        }
+                        p.item()?;
+                        if map {
+                            p.out.push_str(": ");
+                            p.item()?;
+                        }
+                    }
+                    p.out.push(if map { '}' } else { ']' });
+                    Ok(())
+                });
+            }
+            _ => {
+                self.out.push_str(&arg.to_string());
+                self.out.push_str(indicator);
+                self.out.push('(');
+                self.nested(|p| p.item())?;
+                self.out.push(')');
+                return Ok(());
+            }
+        }
+        self.out.push_str(indicator);
+        Ok(())
+    }
+
+    fn string(&mut self, major: u8, len: u64, indicator: &str) -> Result<()> {
+        let len = self.length(len)?;
+        let start = self.index;
+        let bytes = self.take(len)?;
+        if major == 2 {
+            self.out.push_str("h'");
+            for byte in bytes {
+                self.out.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+                self.out.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+            }
+            self.out.push('\'');
+        } else {
+            let text = str::from_utf8(bytes).map_err(|e| {
+                Error::syntax(ErrorCode::InvalidUtf8, (start + e.valid_up_to()) as u64)
+            })?;
+            self.text(text);
+        }
+        self.out.push_str(indicator);
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) {
+        self.out.push('"');
+        for c in text.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                '\u{08}' => self.out.push_str("\\b"),
+                '\u{0c}' => self.out.push_str("\\f"),
+                '\u{00}'..='\u{1f}' => {
+                    self.out.push_str("\\u00");
+                    self.out.push(HEX_DIGITS[(c as usize) >> 4] as char);
+                    self.out.push(HEX_DIGITS[(c as usize) & 0x0f] as char);
+                }
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn indefinite_string(&mut self, major: u8) -> Result<()> {
+        if self.peek() == Some(0xff) {
+            self.index += 1;
+            self.out.push_str(if major == 2 { "''_" } else { "\"\"_" });
+            return Ok(());
+        }
+        self.out.push_str("(_ ");
+        let mut first = true;
+        loop {
+            let byte = self.next()?;
+            if byte == 0xff {
+                break;
+            }
+            if byte >> 5 != major || byte & 0x1f > 0x1b {
+                return Err(self.error(ErrorCode::UnexpectedCode));
+            }
+            if !first {
+                self.out.push_str(", ");
+            }
+            first = false;
+            let (len, indicator) = self.argument(byte & 0x1f)?;
+            self.string(major, len, indicator)?;
+        }
+        self.out.push(')');
+        Ok(())
+    }
+
+    fn indefinite_items(&mut self, open: &str, close: &str, map: bool) -> Result<()> {
+        self.out.push_str(open);
+        let mut first = true;
+        loop {
+            match self.peek() {
+                Some(0xff) => {
+                    self.index += 1;
+                    break;
+                }
+                Some(_) => {}
+                None if map => return Err(self.error(ErrorCode::EofWhileParsingMap)),
+                None => return Err(self.error(ErrorCode::EofWhileParsingArray)),
+            }
+            if !first {
+                self.out.push_str(", ");
+            }
+            first = false;
+            self.item()?;
+            if map {
+                self.out.push_str(": ");
+                self.item()?;
+            }
+        }
+        self.out.push_str(close);
+        Ok(())
+    }
+
+    fn simple(&mut self, info: u8) -> Result<()> {
+        match info {
+            0x00..=0x13 => self.simple_value(info),
+            0x14 => self.out.push_str("false"),
+            0x15 => self.out.push_str("true"),
+            0x16 => self.out.push_str("null"),
+            0x17 => self.out.push_str("undefined"),
+            0x18 => {
+                let value = self.next()?;
+                if value < 0x20 {
+                    return Err(self.error(ErrorCode::UnassignedCode));
+                }
+                self.simple_value(value);
+            }
+            0x19 => {
+                let value = f16::from_bits(u16::from_be_bytes(self.take_array()?));
+                self.float(f64::from(f32::from(value)), "");
+            }
+            0x1a => {
+                let value = f32::from_bits(u32::from_be_bytes(self.take_array()?));
+                let shorter =
+                    value.is_nan() || f32::from(f16::from_f32(value)).to_bits() == value.to_bits();
+                self.float(f64::from(value), if shorter { "_2" } else { "" });
+            }
+            0x1b => {
+                let value = f64::from_bits(u64::from_be_bytes(self.take_array()?));
+                let shorter =
+                    value.is_nan() || f64::from(value as f32).to_bits() == value.to_bits();
+                self.float(value, if shorter { "_3" } else { "" });
+            }
+            0x1f => return Err(self.error(ErrorCode::UnexpectedCode)),
+            _ => return Err(self.error(ErrorCode::UnassignedCode)),
+        }
+        Ok(())
+    }
+
+    fn simple_value(&mut self, value: u8) {
+        self.out.push_str("simple(");
+        self.out.push_str(&value.to_string());
+        self.out.push(')');
+    }
+
+    fn float(&mut self, value: f64, indicator: &str) {
+        if value.is_nan() {
+            self.out.push_str("NaN");
+        } else if value.is_infinite() {
+            self.out
+                .push_str(if value > 0.0 { "Infinity" } else { "-Infinity" });
+        } else {
+            // `Debug` always includes a fraction or an exponent and uses the shortest
+            // representation that converts back to the same value.
+            self.out.push_str(&format!("{:?}", value));
+        }
+        self.out.push_str(indicator);
+    }
+}
+
+/// Parses a single data item in diagnostic notation and returns its encoding.
+///
+/// Besides the notation produced by `to_string` this accepts byte strings written as
+/// `'text'` or in base64 as `b64'AQI='`, unsigned integers in hexadecimal like `0x1f`, and
+/// comments between slashes. Without an encoding indicator every item is encoded in its
+/// shortest form.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_cbor::diag;
+///
+/// let bytes = diag::parse("[_ 1(1363896240), h'0102', 1_1, Infinity]").unwrap();
+/// assert_eq!(
+///     bytes,
+///     b"\x9f\xc1\x1a\x51\x4b\x67\xb0\x42\x01\x02\x19\x00\x01\xf9\x7c\x00\xff"
+/// );
+/// ```
+pub fn parse(input: &str) -> Result<Vec<u8>> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        index: 0,
+        remaining_depth: 128,
+    };
+    let mut out = Vec::new();
+    parser.item(&mut out)?;
+    parser.whitespace()?;
+    if parser.index != parser.input.len() {
+        return Err(parser.error());
+    }
+    Ok(out)
+}
+
+/// Parses a single data item in diagnostic notation into a `Value`.
+#[cfg(feature = "std")]
+pub fn parse_value(input: &str) -> Result<Value> {
+    crate::from_slice(&parse(input)?)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    index: usize,
+    remaining_depth: u8,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> Error {
+        Error::syntax(ErrorCode::InvalidDiagnostic, self.index as u64)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.index).cloned()
+    }
+
+    fn starts_with(&self, prefix: &[u8]) -> bool {
+        self.input[self.index..].starts_with(prefix)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.peek() == Some(byte) {
+            self.index += 1;
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Skips whitespace and comments.
+    fn whitespace(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') => self.index += 1,
+                Some(b'/') => {
+                    self.index += 1;
+                    match self.input[self.index..].iter().position(|&b| b == b'/') {
+                        Some(len) => self.index += len + 1,
+                        None => return Err(self.error()),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Reads an encoding indicator `_0` to `_3`.
+    fn indicator(&mut self) -> Option<u8> {
+        match self.input.get(self.index..self.index + 2) {
+            Some(&[b'_', digit @ b'0'..=b'3']) => {
+                self.index += 2;
+                Some(digit - b'0')
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads the `_` that marks an empty indefinite-length string or an indefinite-length array
+    /// or map.
+    fn indefinite(&mut self) -> bool {
+        if self.peek() == Some(b'_') {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn head(&self, out: &mut Vec<u8>, major: u8, value: u64, indicator: Option<u8>) -> Result<()> {
+        let size = match indicator {
+            Some(size) => size,
+            None if value < 0x18 => {
+                out.push(major << 5 | value as u8);
+                return Ok(());
+            }
+            None if value <= 0xff => 0,
+            None if value <= 0xffff => 1,
+            None if value <= 0xffff_ffff => 2,
+            None => 3,
+        };
+        let len = 1 << size;
+        if len < 8 && value >> (8 * len) != 0 {
+            return Err(self.error());
+        }
+        out.push(major << 5 | (0x18 + size));
+        out.extend_from_slice(&value.to_be_bytes()[8 - len..]);
+        Ok(())
+    }
+
+    fn nested<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.remaining_depth -= 1;
+        if self.remaining_depth == 0 {
+            return Err(Error::syntax(
+                ErrorCode::RecursionLimitExceeded,
+                self.index as u64,
+            ));
+        }
+        let r = f(self);
+        self.remaining_depth += 1;
+        r
+    }
+
+    fn item(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.whitespace()?;
+        match self.peek() {
+            Some(b'[') => self.nested(|p| p.collection(out, false)),
+            Some(b'{') => self.nested(|p| p.collection(out, true)),
+            Some(b'(') => self.indefinite_string(out),
+            Some(b'"') => {
+                let text = self.quoted(b'"')?;
+                self.string(out, 3, &text)
+            }
+            Some(b'\'') => {
+                let bytes = self.quoted(b'\'')?;
+                self.string(out, 2, &bytes)
+            }
+            Some(b'h') if self.starts_with(b"h'") => {
+                let bytes = self.hex()?;
+                self.string(out, 2, &bytes)
+            }
+            Some(b'b') if self.starts_with(b"b64'") => {
+                let bytes = self.base64()?;
+                self.string(out, 2, &bytes)
+            }
+            Some(b'-') | Some(b'0'..=b'9') => self.number(out),
+            _ => self.keyword(out),
+        }
+    }
+
+    fn collection(&mut self, out: &mut Vec<u8>, map: bool) -> Result<()> {
+        let (major, close) = if map { (5, b'}') } else { (4, b']') };
+        self.index += 1;
+        let indicator = self.indicator();
+        let indefinite = indicator.is_none() && self.indefinite();
+        let mut items = Vec::new();
+        let mut len = 0;
+        self.whitespace()?;
+        if self.peek() == Some(close) {
+            self.index += 1;
+        } else {
+            loop {
+                self.item(&mut items)?;
+                if map {
+                    self.whitespace()?;
+                    self.expect(b':')?;
+                    self.item(&mut items)?;
+                }
+                len += 1;
+                self.whitespace()?;
+                match self.peek() {
+                    Some(b',') => self.index += 1,
+                    Some(byte) if byte == close => {
+                        self.index += 1;
+                        break;
+                    }
+                    _ => return Err(self.error()),
+                }
+            }
+        }
+        if indefinite {
+            out.push(major << 5 | 0x1f);
+            out.extend_from_slice(&items);
+            out.push(0xff);
+        } else {
+            self.head(out, major, len, indicator)?;
+            out.extend_from_slice(&items);
+        }
+        Ok(())
+    }
+
+    /// Parses the chunks of an indefinite-length string like `(_ "a", "b")`.
+    fn indefinite_string(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.index += 1;
+        self.expect(b'_')?;
+        let mut chunks = Vec::new();
+        let mut major = None;
+        loop {
+            let start = self.index;
+            let chunk_start = chunks.len();
+            self.item(&mut chunks)?;
+            let initial = chunks[chunk_start];
+            if initial & 0x1f == 0x1f || (initial >> 5 != 2 && initial >> 5 != 3) {
+                return Err(Error::syntax(ErrorCode::InvalidDiagnostic, start as u64));
+            }
+            if *major.get_or_insert(initial >> 5) != initial >> 5 {
+                return Err(Error::syntax(ErrorCode::InvalidDiagnostic, start as u64));
+            }
+            self.whitespace()?;
+            match self.peek() {
+                Some(b',') => self.index += 1,
+                Some(b')') => {
+                    self.index += 1;
+                    break;
+                }
+                _ => return Err(self.error()),
+            }
+        }
+        out.push(major.unwrap_or(2) << 5 | 0x1f);
+        out.extend_from_slice(&chunks);
+        out.push(0xff);
+        Ok(())
+    }
+
+    /// Writes a byte or text string followed by an optional encoding indicator.
+    fn string(&mut self, out: &mut Vec<u8>, major: u8, bytes: &[u8]) -> Result<()> {
+        let indicator = self.indicator();
+        if indicator.is_none() && self.indefinite() {
+            // `''_` and `""_` are empty indefinite-length strings.
+            if !bytes.is_empty() {
+                return Err(self.error());
+            }
+            out.push(major << 5 | 0x1f);
+            out.push(0xff);
+            return Ok(());
+        }
+        self.head(out, major, bytes.len() as u64, indicator)?;
+        out.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Reads a string in double or single quotes with JSON escapes.
+    fn quoted(&mut self, quote: u8) -> Result<Vec<u8>> {
+        self.index += 1;
+        let mut buf = Vec::new();
+        loop {
+            let byte = self.peek().ok_or_else(|| self.error())?;
+            self.index += 1;
+            match byte {
+                b'\\' => {
+                    let escape = self.peek().ok_or_else(|| self.error())?;
+                    self.index += 1;
+                    let c = match escape {
+                        b'"' | b'\'' | b'\\' | b'/' => escape as char,
+                        b'b' => '\u{08}',
+                        b'f' => '\u{0c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(self.error()),
+                    };
+                    let mut utf8 = [0; 4];
+                    buf.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+                }
+                _ if byte == quote => return Ok(buf),
+                _ => buf.push(byte),
+            }
+        }
+    }
+
+    /// Reads the digits of a `\u` escape, which may be followed by a low surrogate.
+    fn unicode_escape(&mut self) -> Result<char> {
+        let high = self.hex_u16()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if !self.starts_with(b"\\u") {
+                return Err(self.error());
+            }
+            self.index += 2;
+            let low = self.hex_u16()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error());
+            }
+            0x10000 + ((u32::from(high) - 0xd800) << 10) + (u32::from(low) - 0xdc00)
+        } else {
+            u32::from(high)
+        };
+        core::char::from_u32(code).ok_or_else(|| self.error())
+    }
+
+    fn hex_u16(&mut self) -> Result<u16> {
+        let digits = self
+            .input
+            .get(self.index..self.index + 4)
+            .ok_or_else(|| self.error())?;
+        let digits = str::from_utf8(digits).map_err(|_| self.error())?;
+        let value = u16::from_str_radix(digits, 16).map_err(|_| self.error())?;
+        self.index += 4;
+        Ok(value)
+    }
+
+    fn hex(&mut self) -> Result<Vec<u8>> {
+        self.index += 2;
+        let mut bytes = Vec::new();
+        let mut high = None;
+        loop {
+            let byte = self.peek().ok_or_else(|| self.error())?;
+            let digit = match byte {
+                b'\'' if high.is_none() => {
+                    self.index += 1;
+                    return Ok(bytes);
+                }
+                b' ' | b'\t' | b'\r' | b'\n' => {
+                    self.index += 1;
+                    continue;
+                }
+                b'0'..=b'9' => byte - b'0',
+                b'a'..=b'f' => byte - b'a' + 10,
+                b'A'..=b'F' => byte - b'A' + 10,
+                _ => return Err(self.error()),
+            };
+            self.index += 1;
+            match high.take() {
+                Some(high) => bytes.push(high << 4 | digit),
+                None => high = Some(digit),
+            }
+        }
+    }
+
+    /// Reads a byte string in base64, with either the standard or the URL-safe alphabet and
+    /// optional padding.
+    fn base64(&mut self) -> Result<Vec<u8>> {
+        self.index += 4;
+        let mut bytes = Vec::new();
+        let mut bits = 0u32;
+        let mut len = 0;
+        let mut padding = false;
+        loop {
+            let byte = self.peek().ok_or_else(|| self.error())?;
+            let value = match byte {
+                b'\'' => break,
+                b'=' => {
+                    padding = true;
+                    self.index += 1;
+                    continue;
+                }
+                _ if padding => return Err(self.error()),
+                b'A'..=b'Z' => byte - b'A',
+                b'a'..=b'z' => byte - b'a' + 26,
+                b'0'..=b'9' => byte - b'0' + 52,
+                b'+' | b'-' => 62,
+                b'/' | b'_' => 63,
+                _ => return Err(self.error()),
+            };
+            self.index += 1;
+            bits = bits << 6 | u32::from(value);
+            len += 1;
+            if len % 4 == 0 {
+                bytes.extend_from_slice(&bits.to_be_bytes()[1..]);
+                bits = 0;
+            }
+        }
+        match len % 4 {
+            0 => {}
+            2 => bytes.push((bits >> 4) as u8),
+            3 => bytes.extend_from_slice(&((bits >> 2) as u16).to_be_bytes()),
+            _ => return Err(self.error()),
+        }
+        self.index += 1;
+        Ok(bytes)
+    }
+
+    fn number(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        let start = self.index;
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.index += 1;
+            if self.starts_with(b"Infinity") {
+                self.index += 8;
+                let indicator = self.indicator();
+                return self.float(out, -f64::from_bits(INFINITY_BITS), indicator);
+            }
+        }
+        let magnitude = if !negative && self.starts_with(b"0x") {
+            self.index += 2;
+            self.digits(16)?
+        } else {
+            let digits_start = self.index;
+            self.skip_digits();
+            let mut float = false;
+            if self.peek() == Some(b'.') {
+                self.index += 1;
+                self.skip_digits();
+                float = true;
+            }
+            if let Some(b'e') | Some(b'E') = self.peek() {
+                self.index += 1;
+                if let Some(b'+') | Some(b'-') = self.peek() {
+                    self.index += 1;
+                }
+                self.skip_digits();
+                float = true;
+            }
+            if float {
+                let text = str::from_utf8(&self.input[start..self.index]).expect("ASCII");
+                let value = text
+                    .parse()
+                    .map_err(|_| Error::syntax(ErrorCode::InvalidDiagnostic, start as u64))?;
+                let indicator = self.indicator();
+                return self.float(out, value, indicator);
+            }
+            self.index = digits_start;
+            self.digits(10)?
+        };
+        let indicator = self.indicator();
+        if negative && magnitude > 0 {
+            let value = u64::try_from(magnitude - 1).map_err(|_| self.error())?;
+            return self.head(out, 1, value, indicator);
+        }
+        let value = u64::try_from(magnitude).map_err(|_| self.error())?;
+        if self.peek() == Some(b'(') {
+            self.index += 1;
+            self.head(out, 6, value, indicator)?;
+            self.nested(|p| p.item(out))?;
+            self.whitespace()?;
+            return self.expect(b')');
+        }
+        self.head(out, 0, value, indicator)
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.index += 1;
+        }
+    }
+
+    fn digits(&mut self, radix: u32) -> Result<u128> {
+        let start = self.index;
+        let mut value = 0u128;
+        while let Some(digit) = self.peek().and_then(|b| (b as char).to_digit(radix)) {
+            value = value
+                .checked_mul(radix.into())
+                .and_then(|v| v.checked_add(digit.into()))
+                .ok_or_else(|| self.error())?;
+            self.index += 1;
+        }
+        if self.index == start {
+            return Err(self.error());
+        }
+        Ok(value)
+    }
+
+    fn float(&self, out: &mut Vec<u8>, value: f64, indicator: Option<u8>) -> Result<()> {
+        let half = f16::from_f64(value);
+        let single = value as f32;
+        let fits_half = value.is_nan() || f64::from(half).to_bits() == value.to_bits();
+        let fits_single = value.is_nan() || f64::from(single).to_bits() == value.to_bits();
+        let size = match indicator {
+            Some(size) => size,
+            None if fits_half => 1,
+            None if fits_single => 2,
+            None => 3,
+        };
+        match size {
+            1 if fits_half => {
+                let bits = if value.is_nan() {
+                    0x7e00
+                } else {
+                    half.to_bits()
+                };
+                out.push(0xf9);
+                out.extend_from_slice(&bits.to_be_bytes());
+            }
+            2 if fits_single => {
+                let bits = if value.is_nan() {
+                    0x7fc0_0000
+                } else {
+                    single.to_bits()
+                };
+                out.push(0xfa);
+                out.extend_from_slice(&bits.to_be_bytes());
+            }
+            3 => {
+                let bits = if value.is_nan() {
+                    NAN_BITS
+                } else {
+                    value.to_bits()
+                };
+                out.push(0xfb);
+                out.extend_from_slice(&bits.to_be_bytes());
+            }
+            _ => return Err(self.error()),
+        }
+        Ok(())
+    }
+
+    fn keyword(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        let start = self.index;
+        while let Some(b'a'..=b'z') | Some(b'A'..=b'Z') = self.peek() {
+            self.index += 1;
+        }
+        match &self.input[start..self.index] {
+            b"false" => out.push(0xf4),
+            b"true" => out.push(0xf5),
+            b"null" => out.push(0xf6),
+            b"undefined" => out.push(0xf7),
+            b"NaN" => {
+                let indicator = self.indicator();
+                return self.float(out, f64::from_bits(NAN_BITS), indicator);
+            }
+            b"Infinity" => {
+                let indicator = self.indicator();
+                return self.float(out, f64::from_bits(INFINITY_BITS), indicator);
+            }
+            b"simple" => {
+                self.expect(b'(')?;
+                self.whitespace()?;
+                let value = u8::try_from(self.digits(10)?).map_err(|_| self.error())?;
+                match value {
+                    0x00..=0x17 => out.push(0xe0 | value),
+                    0x18..=0x1f => return Err(self.error()),
+                    _ => out.extend_from_slice(&[0xf8, value]),
+                }
+                self.whitespace()?;
+                self.expect(b')')?;
+            }
+            _ => return Err(Error::syntax(ErrorCode::InvalidDiagnostic, start as u64)),
+        }
+        Ok(())
+    }
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const INFINITY_BITS: u64 = 0x7ff0_0000_0000_0000;
+const NAN_BITS: u64 = 0x7ff8_0000_0000_0000;
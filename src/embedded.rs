@@ -0,0 +1,177 @@
+//! Encoded CBOR data items embedded in a byte string.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize, DeserializeOwned, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use crate::error::Result;
+use crate::raw::RawBytes;
+
+/// The name of the newtype struct that embedded data items are serialized as. It contains the
+/// encoded data item as a byte string.
+pub(crate) const CBOR_EMBEDDED_NAME: &str = "\0cbor_embedded";
+
+/// A data item encoded in a byte string with tag 24.
+///
+/// An `Embedded` keeps both the decoded value and its encoding. Serializing it writes the
+/// encoding that was decoded or created before, so the bytes can be signed or verified
+/// independently of the enclosing message, as done by COSE.
+///
+/// A `serde_cbor` deserializer decodes the embedded data item with the same options as the
+/// enclosing message, for example strict mode or the limits on lengths and nesting. Other data
+/// formats see an `Embedded` as a byte string, which is decoded with the default options.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_cbor::embedded::Embedded;
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Signed {
+///     payload: Embedded<Vec<u32>>,
+///     signature: u32,
+/// }
+///
+/// let payload = Embedded::new(vec![1, 2]).unwrap();
+/// assert_eq!(payload.as_bytes(), b"\x82\x01\x02");
+///
+/// let signed = Signed { payload, signature: 3 };
+/// let bytes = serde_cbor::to_vec(&signed).unwrap();
+/// // {"payload": 24(h'820102'), "signature": 3}
+/// assert_eq!(bytes, &b"\xa2\x67payload\xd8\x18\x43\x82\x01\x02\x69signature\x03"[..]);
+///
+/// let signed: Signed = serde_cbor::from_slice(&bytes).unwrap();
+/// assert_eq!(signed.payload.value(), &vec![1, 2]);
+/// assert_eq!(signed.payload.as_bytes(), b"\x82\x01\x02");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Embedded<T> {
+    value: T,
+    bytes: Vec<u8>,
+}
+
+impl<T: Serialize> Embedded<T> {
+    /// Encodes a value to be embedded.
+    pub fn new(value: T) -> Result<Embedded<T>> {
+        let bytes = crate::to_vec(&value)?;
+        Ok(Embedded { value, bytes })
+    }
+}
+
+impl<T: DeserializeOwned> Embedded<T> {
+    /// Decodes an embedded data item from its encoding.
+    ///
+    /// Returns an error if `bytes` isn't exactly one data item of type `T`.
+    pub fn from_vec(bytes: Vec<u8>) -> Result<Embedded<T>> {
+        let value = crate::from_slice(&bytes)?;
+        Ok(Embedded { value, bytes })
+    }
+}
+
+impl<T> Embedded<T> {
+    /// Returns the decoded value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the encoding of the value.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the decoded value, consuming the `Embedded`.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Returns the decoded value and its encoding, consuming the `Embedded`.
+    pub fn into_parts(self) -> (T, Vec<u8>) {
+        (self.value, self.bytes)
+    }
+}
+
+impl<T> Serialize for Embedded<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
+        s.serialize_newtype_struct(CBOR_EMBEDDED_NAME, &RawBytes(&self.bytes))
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Embedded<T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> core::result::Result<Self, D::Error> {
+        struct EmbeddedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: DeserializeOwned> de::Visitor<'de> for EmbeddedVisitor<T> {
+            type Value = Embedded<T>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("an embedded CBOR data item")
+            }
+
+            // A `serde_cbor` deserializer passes the encoding followed by the decoded value.
+            fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Embedded<T>, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let ByteBuf(bytes) = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let value = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                Ok(Embedded { value, bytes })
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> core::result::Result<Embedded<T>, E> {
+                self.visit_byte_buf(v.to_vec())
+            }
+
+            fn visit_byte_buf<E: de::Error>(
+                self,
+                v: Vec<u8>,
+            ) -> core::result::Result<Embedded<T>, E> {
+                Embedded::from_vec(v).map_err(de::Error::custom)
+            }
+
+            fn visit_newtype_struct<D>(self, d: D) -> core::result::Result<Embedded<T>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                d.deserialize_byte_buf(self)
+            }
+        }
+
+        d.deserialize_newtype_struct(CBOR_EMBEDDED_NAME, EmbeddedVisitor(PhantomData))
+    }
+}
+
+/// The contents of a byte string, used to deserialize byte strings inside this crate.
+pub(crate) struct ByteBuf(pub(crate) Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> core::result::Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> de::Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("a byte string")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> core::result::Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> core::result::Result<ByteBuf, E> {
+                Ok(ByteBuf(v))
+            }
+        }
+
+        d.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
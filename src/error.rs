@@ -133,6 +133,7 @@ impl Error {
             #[cfg(not(feature = "std"))]
             ErrorCode::Io => Category::Io,
             ErrorCode::ScratchTooSmall => Category::Io,
+            ErrorCode::NumberOutOfRange | ErrorCode::UnexpectedTag => Category::Data,
             ErrorCode::EofWhileParsingValue
             | ErrorCode::EofWhileParsingArray
             | ErrorCode::EofWhileParsingMap => Category::Eof,
@@ -144,8 +145,20 @@ impl Error {
             | ErrorCode::ArrayTooShort
             | ErrorCode::ArrayTooLong
             | ErrorCode::RecursionLimitExceeded
+            | ErrorCode::LengthLimitExceeded
+            | ErrorCode::ElementLimitExceeded
+            | ErrorCode::AllocationLimitExceeded
+            | ErrorCode::FrameLimitExceeded
             | ErrorCode::WrongEnumFormat
-            | ErrorCode::WrongStructFormat => Category::Syntax,
+            | ErrorCode::WrongStructFormat
+            | ErrorCode::NonShortestArgument
+            | ErrorCode::NonShortestFloat
+            | ErrorCode::IndefiniteLength
+            | ErrorCode::UnsortedMapKeys
+            | ErrorCode::DuplicateKey
+            | ErrorCode::InvalidDiagnostic
+            | ErrorCode::InvalidNumber
+            | ErrorCode::InvalidDateTime => Category::Syntax,
         }
     }
 
@@ -284,8 +297,29 @@ pub(crate) enum ErrorCode {
     ArrayTooShort,
     ArrayTooLong,
     RecursionLimitExceeded,
+    LengthLimitExceeded,
+    ElementLimitExceeded,
+    AllocationLimitExceeded,
+    #[allow(unused)]
+    FrameLimitExceeded,
+    NumberOutOfRange,
     WrongEnumFormat,
     WrongStructFormat,
+    NonShortestArgument,
+    NonShortestFloat,
+    IndefiniteLength,
+    #[allow(unused)]
+    UnsortedMapKeys,
+    #[allow(unused)]
+    DuplicateKey,
+    #[allow(unused)]
+    InvalidDiagnostic,
+    #[allow(unused)]
+    InvalidNumber,
+    #[allow(unused)]
+    InvalidDateTime,
+    #[allow(unused)]
+    UnexpectedTag,
 }
 
 impl fmt::Display for ErrorCode {
@@ -311,8 +345,22 @@ impl fmt::Display for ErrorCode {
             ErrorCode::ArrayTooShort => f.write_str("array too short"),
             ErrorCode::ArrayTooLong => f.write_str("array too long"),
             ErrorCode::RecursionLimitExceeded => f.write_str("recursion limit exceeded"),
+            ErrorCode::LengthLimitExceeded => f.write_str("string length limit exceeded"),
+            ErrorCode::ElementLimitExceeded => f.write_str("collection size limit exceeded"),
+            ErrorCode::AllocationLimitExceeded => f.write_str("allocation limit exceeded"),
+            ErrorCode::FrameLimitExceeded => f.write_str("frame size limit exceeded"),
+            ErrorCode::NumberOutOfRange => f.write_str("number out of range"),
             ErrorCode::WrongEnumFormat => f.write_str("wrong enum format"),
             ErrorCode::WrongStructFormat => f.write_str("wrong struct format"),
+            ErrorCode::NonShortestArgument => f.write_str("argument not in shortest form"),
+            ErrorCode::NonShortestFloat => f.write_str("float not in shortest form"),
+            ErrorCode::IndefiniteLength => f.write_str("indefinite-length item"),
+            ErrorCode::UnsortedMapKeys => f.write_str("map keys not in canonical order"),
+            ErrorCode::DuplicateKey => f.write_str("duplicate map key"),
+            ErrorCode::InvalidDiagnostic => f.write_str("invalid diagnostic notation"),
+            ErrorCode::InvalidNumber => f.write_str("invalid number"),
+            ErrorCode::InvalidDateTime => f.write_str("invalid date or time"),
+            ErrorCode::UnexpectedTag => f.write_str("unexpected tag"),
         }
     }
 }
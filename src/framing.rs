@@ -0,0 +1,298 @@
+//! Framing of CBOR messages on byte streams like TCP or Unix sockets.
+//!
+//! A [`FrameEncoder`] writes one message per call and a [`FrameDecoder`] reads them back. Two
+//! framings are supported:
+//!
+//! * [`Framing::Sequence`] writes the messages back to back as a CBOR sequence (RFC 8742).
+//!   CBOR data items are self-delimiting, so the decoder finds the end of a message by parsing
+//!   it.
+//! * [`Framing::LengthPrefixed`] precedes every message with its length as a big-endian `u32`.
+//!   The decoder can skip a message it fails to decode and continue with the next one.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde_cbor::framing::{FrameDecoder, FrameEncoder, Framing};
+//!
+//! let mut encoder = FrameEncoder::new(Vec::new(), Framing::LengthPrefixed);
+//! encoder.encode(&"hello").unwrap();
+//! encoder.encode(&[1, 2, 3]).unwrap();
+//! let bytes = encoder.into_inner();
+//! assert_eq!(&bytes[..10], b"\x00\x00\x00\x06\x65hello");
+//!
+//! let mut decoder = FrameDecoder::new(&bytes[..], Framing::LengthPrefixed);
+//! assert_eq!(decoder.decode::<String>().unwrap().unwrap(), "hello");
+//! assert_eq!(decoder.decode::<Vec<u32>>().unwrap().unwrap(), [1, 2, 3]);
+//! assert!(decoder.decode::<Vec<u32>>().unwrap().is_none());
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::de::Deserializer;
+use crate::error::{Error, ErrorCode, Result};
+use crate::push::{Configure, Scanner};
+use crate::ser::Serializer;
+
+/// The size of the length prefix of `Framing::LengthPrefixed`.
+const PREFIX_LEN: usize = 4;
+
+/// The number of bytes a `FrameDecoder` tries to read at once.
+const CHUNK_LEN: usize = 8 * 1024;
+
+/// How messages are delimited on a byte stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framing {
+    /// Messages are written back to back as a CBOR sequence.
+    Sequence,
+    /// Every message is preceded by its length as a big-endian `u32`.
+    LengthPrefixed,
+}
+
+/// Writes framed CBOR messages to a writer.
+#[derive(Debug)]
+pub struct FrameEncoder<W> {
+    writer: W,
+    framing: Framing,
+    buffer: Vec<u8>,
+}
+
+impl<W> FrameEncoder<W>
+where
+    W: io::Write,
+{
+    /// Creates a new encoder that writes messages with the given framing.
+    pub fn new(writer: W, framing: Framing) -> FrameEncoder<W> {
+        FrameEncoder {
+            writer,
+            framing,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Serializes a value and writes it as one message.
+    ///
+    /// The message is serialized into a buffer first, so nothing is written if serialization
+    /// fails.
+    pub fn encode<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.buffer.clear();
+        if self.framing == Framing::LengthPrefixed {
+            self.buffer.extend_from_slice(&[0; PREFIX_LEN]);
+        }
+        value.serialize(&mut Serializer::new(&mut self.buffer))?;
+        if self.framing == Framing::LengthPrefixed {
+            let len = u32::try_from(self.buffer.len() - PREFIX_LEN)
+                .map_err(|_| Error::syntax(ErrorCode::LengthOutOfRange, 0))?;
+            self.buffer[..PREFIX_LEN].copy_from_slice(&len.to_be_bytes());
+        }
+        self.writer.write_all(&self.buffer).map_err(Error::io)
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Unwraps the writer from the encoder.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads framed CBOR messages from a reader.
+///
+/// Bytes are read in chunks and buffered until a complete message is available. If the reader
+/// fails, for example with `io::ErrorKind::WouldBlock` on a non-blocking socket, the error is
+/// returned and the buffered bytes are kept, so `decode` can be called again once more data
+/// arrives. With `Framing::Sequence` the buffered bytes are scanned incrementally, so every byte
+/// is scanned only once no matter how the input is split.
+pub struct FrameDecoder<R> {
+    reader: R,
+    framing: Framing,
+    buffer: Vec<u8>,
+    scanner: Scanner,
+    /// The offset of the start of `buffer` in the input.
+    offset: u64,
+    max_frame_len: Option<usize>,
+    configure: Configure,
+}
+
+impl<R> FrameDecoder<R>
+where
+    R: io::Read,
+{
+    /// Creates a new decoder that reads messages with the given framing.
+    pub fn new(reader: R, framing: Framing) -> FrameDecoder<R> {
+        FrameDecoder {
+            reader,
+            framing,
+            buffer: Vec::new(),
+            scanner: Scanner::default(),
+            offset: 0,
+            max_frame_len: None,
+            configure: |de| de,
+        }
+    }
+
+    /// Limit the length in bytes of a message, not counting the length prefix.
+    ///
+    /// Longer messages are rejected before they are buffered completely. By default the length
+    /// is not limited.
+    pub fn max_frame_len(mut self, len: usize) -> Self {
+        self.max_frame_len = Some(len);
+        self
+    }
+
+    /// Sets the options of the deserializer that decodes each message.
+    ///
+    /// By default messages are decoded with the default options of `from_slice`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_cbor::framing::{FrameDecoder, Framing};
+    ///
+    /// // {"a": 1, "a": 2}
+    /// let bytes = b"\xa2\x61a\x01\x61a\x02";
+    /// let mut decoder = FrameDecoder::new(&bytes[..], Framing::Sequence)
+    ///     .configure(|de| de.strict().max_length(16));
+    /// assert!(decoder.decode::<serde_cbor::Value>().is_err());
+    /// ```
+    pub fn configure(mut self, configure: Configure) -> Self {
+        self.configure = configure;
+        self
+    }
+
+    /// Reads and decodes the next message.
+    ///
+    /// Returns `Ok(None)` if the reader ends between two messages and an EOF error if it ends
+    /// within a message. With `Framing::LengthPrefixed` a message that fails to decode is
+    /// skipped, so the next call continues with the following message.
+    pub fn decode<T>(&mut self) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        loop {
+            if let Some((start, end)) = self.next_frame()? {
+                let mut de = (self.configure)(Deserializer::from_slice(&self.buffer[start..end]));
+                let result = T::deserialize(&mut de).and_then(|value| {
+                    de.end()?;
+                    Ok(value)
+                });
+                self.buffer.drain(..end);
+                self.offset += end as u64;
+                return result.map(Some);
+            }
+            if !self.fill()? {
+                return if self.buffer.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(Error::syntax(
+                        ErrorCode::EofWhileParsingValue,
+                        self.offset + self.buffer.len() as u64,
+                    ))
+                };
+            }
+        }
+    }
+
+    /// Returns the bytes that have been read but not decoded yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Unwraps the reader from the decoder, discarding any buffered bytes.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Returns the range of the first message in the buffer if it is complete.
+    fn next_frame(&mut self) -> Result<Option<(usize, usize)>> {
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+        let (start, len) = match self.framing {
+            Framing::Sequence => {
+                let needed = self.scanner.scan(&self.buffer, self.offset)?;
+                let len = self.scanner.pos().saturating_add(needed.unwrap_or(0));
+                if let Some(max) = self.max_frame_len {
+                    if len > max {
+                        return Err(Error::syntax(ErrorCode::FrameLimitExceeded, self.offset));
+                    }
+                }
+                if needed.is_some() {
+                    return Ok(None);
+                }
+                (0, self.scanner.finish())
+            }
+            Framing::LengthPrefixed => {
+                if self.buffer.len() < PREFIX_LEN {
+                    return Ok(None);
+                }
+                let mut prefix = [0; PREFIX_LEN];
+                prefix.copy_from_slice(&self.buffer[..PREFIX_LEN]);
+                (PREFIX_LEN, u32::from_be_bytes(prefix) as usize)
+            }
+        };
+        match self.max_frame_len {
+            Some(max) if len > max => {
+                Err(Error::syntax(ErrorCode::FrameLimitExceeded, self.offset))
+            }
+            _ if start + len <= self.buffer.len() => Ok(Some((start, start + len))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Reads more bytes into the buffer and returns `false` at the end of the reader.
+    fn fill(&mut self) -> Result<bool> {
+        let mut chunk = [0; CHUNK_LEN];
+        loop {
+            match self.reader.read(&mut chunk) {
+                Ok(0) => return Ok(false),
+                Ok(n) => {
+                    self.buffer.extend_from_slice(&chunk[..n]);
+                    return Ok(true);
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::io(e)),
+            }
+        }
+    }
+}
+
+impl<R> fmt::Debug for FrameDecoder<R>
+where
+    R: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("FrameDecoder")
+            .field("reader", &self.reader)
+            .field("framing", &self.framing)
+            .field("buffer", &self.buffer)
+            .field("offset", &self.offset)
+            .field("max_frame_len", &self.max_frame_len)
+            .finish()
+    }
+}
@@ -80,10 +80,21 @@
 //! contain data, you can add `legacy_enums()` in addition to `packed_format()`, as can seen
 //! in the Serialize using minimal encoding example.
 //!
+//! # Deterministic encoding
+//! Some applications like digital signatures require that the same data is always encoded to the
+//! exact same bytes. Use `Serializer::new(writer).canonical()` or the shorthand
+//! `ser::to_vec_canonical` to produce the deterministic encoding described in [RFC 8949]. Map
+//! entries are sorted by their encoded keys and indefinite-length items are never emitted.
+//! `legacy_canonical()` selects the length-first key order of RFC 7049 instead.
+//! Conversely `Deserializer::strict()` only accepts input that is encoded deterministically.
+//!
+//! [RFC 8949]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2
+//!
 //! # Self describing documents
 //! In some contexts different formats are used but there is no way to declare the format used
 //! out of band. For this reason CBOR has a magic number that may be added before any document.
-//! Self describing documents are created with `serializer.self_describe()`.
+//! Self describing documents are created with `serializer.self_describe()`. `is_cbor()` checks
+//! for the magic number and `Deserializer::strip_self_describe()` removes it while decoding.
 //!
 //! # Examples
 //! Read a CBOR value that is known to be a map of string keys to string values and print it.
@@ -297,22 +308,21 @@
 //! While Serde CBOR strives to support all features of Serde and CBOR
 //! there are a few limitations.
 //!
-//! * [Tags] are ignored during deserialization and can't be emitted during
-//!     serialization. This is because Serde has no concept of tagged
-//!     values. See:&nbsp;[#3]
-//! * Unknown [simple values] cause an `UnassignedCode` error.
-//!     The simple values *False* and *True* are recognized and parsed as bool.
+//! * [Tags] are ignored during deserialization unless they are read with
+//!     `tags::Tagged` or as a `Value`, which requires the `tags` feature.
+//!     Serde itself has no concept of tagged values. See:&nbsp;[#3]
+//! * Unknown [simple values] cause an `UnassignedCode` error unless they are
+//!     deserialized as a `Value`. *False* and *True* are parsed as bool.
 //!     *Null* and *Undefined* are both deserialized as *unit*.
 //!     The *unit* type is serialized as *Null*. See:&nbsp;[#86]
-//! * [128-bit integers] can't be directly encoded in CBOR. If you need them
-//!     store them as a byte string. See:&nbsp;[#77]
+//! * [128-bit integers] outside the 64-bit range are encoded as bignums
+//!     with tag 2 or 3. Bigger integers can be stored in a `value::BigInt`.
 //!
 //! [Tags]: https://tools.ietf.org/html/rfc7049#section-2.4.4
 //! [#3]: https://github.com/pyfisch/cbor/issues/3
 //! [simple values]: https://tools.ietf.org/html/rfc7049#section-3.5
 //! [#86]: https://github.com/pyfisch/cbor/issues/86
 //! [128-bit integers]: https://doc.rust-lang.org/std/primitive.u128.html
-//! [#77]: https://github.com/pyfisch/cbor/issues/77
 
 #![deny(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -325,10 +335,26 @@ extern crate std;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+pub mod async_io;
 pub mod de;
+#[cfg(all(feature = "std", feature = "tags"))]
+pub mod decimal;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod diag;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod embedded;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod framing;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod push;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod raw;
 mod read;
 pub mod ser;
+#[cfg(all(feature = "std", feature = "bytes"))]
+pub mod shared;
 pub mod tags;
 mod write;
 
@@ -343,7 +369,7 @@ pub use crate::de::{Deserializer, StreamDeserializer};
 pub use crate::error::{Error, Result};
 
 #[doc(inline)]
-pub use crate::ser::Serializer;
+pub use crate::ser::{SequenceSerializer, Serializer};
 
 // Convenience functions for serialization and deserialization.
 // These functions are only available in `std` mode.
@@ -355,6 +381,9 @@ pub use crate::de::from_reader;
 #[doc(inline)]
 pub use crate::de::from_slice;
 
+#[doc(inline)]
+pub use crate::de::{is_cbor, validate};
+
 #[cfg(any(feature = "std", feature = "alloc"))]
 #[doc(inline)]
 pub use crate::ser::to_vec;
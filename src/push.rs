@@ -0,0 +1,345 @@
+//! Push-based decoding of CBOR data arriving in chunks.
+//!
+//! A [`PushDecoder`] is fed with byte chunks as they arrive, for example from a non-blocking
+//! socket, and never blocks or returns an EOF error for incomplete input. It scans the data
+//! incrementally and remembers how far it got, so every byte is scanned only once no matter how
+//! the input is split. Once a data item is complete it is deserialized in one go.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde_cbor::push::{PushDecoder, Status};
+//!
+//! let mut decoder = PushDecoder::new();
+//!
+//! // ["a", "bc"] followed by the start of 1000
+//! decoder.feed(b"\x82\x61a\x62b");
+//! assert_eq!(decoder.decode::<Vec<String>>().unwrap(), Status::NeedMore(1));
+//! decoder.feed(b"c\x19\x03");
+//! assert_eq!(
+//!     decoder.decode::<Vec<String>>().unwrap(),
+//!     Status::Item(vec!["a".to_string(), "bc".to_string()])
+//! );
+//! assert_eq!(decoder.decode::<u32>().unwrap(), Status::NeedMore(1));
+//! decoder.feed(b"\xe8");
+//! assert_eq!(decoder.decode::<u32>().unwrap(), Status::Item(1000));
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+use core::fmt;
+use serde::de::DeserializeOwned;
+
+use crate::de::{Deserializer, SliceRead};
+use crate::error::{Error, ErrorCode, Result};
+
+/// The maximum nesting depth of data items, which matches the default of `Deserializer`.
+const MAX_DEPTH: usize = 128;
+
+/// Options applied to the deserializer of every item.
+pub(crate) type Configure = for<'a> fn(Deserializer<SliceRead<'a>>) -> Deserializer<SliceRead<'a>>;
+
+/// The result of trying to decode an item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status<T> {
+    /// The item is incomplete and at least this many more bytes are needed.
+    NeedMore(usize),
+    /// The item was decoded.
+    Item(T),
+}
+
+/// A container whose items are being scanned.
+#[derive(Debug)]
+enum Frame {
+    /// A definite-length array or map, or a tag, with the number of items still missing.
+    Items(u64),
+    /// An indefinite-length array or map with the number of items scanned so far.
+    Indefinite { map: bool, items: u64 },
+    /// An indefinite-length byte or text string with its major type.
+    Chunks(u8),
+}
+
+/// A decoder that is fed with chunks of a CBOR sequence and returns complete items.
+///
+/// The decoder finds the end of an item by scanning the headers of its nested data items and
+/// keeps the partially scanned item in a buffer. Items nested deeper than 128 levels are
+/// rejected while scanning, like the `Deserializer` does by default. Each item is decoded with
+/// the default options of `from_slice` unless [`configure`](#method.configure) is used. If an
+/// item is well-formed but can't be deserialized into the requested type it is skipped, so the
+/// next call to `decode` continues with the following item. After a syntax error the position
+/// of the next item is unknown and the decoder should be discarded.
+pub struct PushDecoder {
+    buffer: Vec<u8>,
+    scanner: Scanner,
+    /// The offset of the start of `buffer` in the input.
+    offset: u64,
+    max_frame_len: Option<usize>,
+    configure: Configure,
+}
+
+impl PushDecoder {
+    /// Creates a new push decoder.
+    pub fn new() -> PushDecoder {
+        PushDecoder {
+            buffer: Vec::new(),
+            scanner: Scanner::default(),
+            offset: 0,
+            max_frame_len: None,
+            configure: |de| de,
+        }
+    }
+
+    /// Limit the length in bytes of an item.
+    ///
+    /// Longer items are rejected before they are buffered completely. By default the length is
+    /// not limited.
+    pub fn max_frame_len(mut self, len: usize) -> Self {
+        self.max_frame_len = Some(len);
+        self
+    }
+
+    /// Sets the options of the deserializer that decodes each item.
+    ///
+    /// By default items are decoded with the default options of `from_slice`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_cbor::push::PushDecoder;
+    ///
+    /// let mut decoder = PushDecoder::new().configure(|de| de.strict());
+    /// // {"a": 1, "a": 2}
+    /// decoder.feed(b"\xa2\x61a\x01\x61a\x02");
+    /// assert!(decoder.decode::<serde_cbor::Value>().is_err());
+    /// ```
+    pub fn configure(mut self, configure: Configure) -> Self {
+        self.configure = configure;
+        self
+    }
+
+    /// Appends a chunk of input to the buffer.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Continues scanning the buffered input and decodes the next item once it is complete.
+    ///
+    /// Returns `Status::NeedMore` with a lower bound for the number of bytes still missing if
+    /// the item is incomplete. Items that are completely buffered are decoded without feeding
+    /// more input.
+    pub fn decode<T>(&mut self) -> Result<Status<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let needed = self.scanner.scan(&self.buffer, self.offset)?;
+        if let Some(max) = self.max_frame_len {
+            if self.scanner.pos().saturating_add(needed.unwrap_or(0)) > max {
+                return Err(Error::syntax(ErrorCode::FrameLimitExceeded, self.offset));
+            }
+        }
+        if let Some(n) = needed {
+            return Ok(Status::NeedMore(n));
+        }
+        let end = self.scanner.finish();
+        let mut de = (self.configure)(Deserializer::from_slice(&self.buffer[..end]));
+        let result = T::deserialize(&mut de).and_then(|value| {
+            de.end()?;
+            Ok(value)
+        });
+        self.buffer.drain(..end);
+        self.offset += end as u64;
+        result.map(Status::Item)
+    }
+
+    /// Returns the bytes that have been fed but not decoded yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns the offset in the input of the next item to be decoded.
+    pub fn byte_offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl Default for PushDecoder {
+    fn default() -> PushDecoder {
+        PushDecoder::new()
+    }
+}
+
+impl fmt::Debug for PushDecoder {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PushDecoder")
+            .field("buffer", &self.buffer)
+            .field("offset", &self.offset)
+            .field("max_frame_len", &self.max_frame_len)
+            .finish()
+    }
+}
+
+/// Finds the end of a data item by scanning the heads of its nested data items.
+///
+/// The scanner keeps its position between calls, so the buffer may grow in between and every
+/// byte is scanned only once.
+#[derive(Debug, Default)]
+pub(crate) struct Scanner {
+    /// The number of bytes that have been scanned.
+    pos: usize,
+    /// The containers enclosing `pos`, the innermost one last.
+    stack: Vec<Frame>,
+}
+
+impl Scanner {
+    /// Returns the number of bytes that have been scanned.
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the length of the scanned item and resets the scanner for the next one.
+    pub(crate) fn finish(&mut self) -> usize {
+        debug_assert!(self.stack.is_empty());
+        let end = self.pos;
+        self.pos = 0;
+        end
+    }
+
+    /// Scans data items until the first item in `input` is complete. Returns the number of
+    /// bytes needed to continue if the input ends before.
+    ///
+    /// `offset` is the offset of `input` in the whole input and only used for errors.
+    pub(crate) fn scan(&mut self, input: &[u8], offset: u64) -> Result<Option<usize>> {
+        let error = |pos: usize, code| Error::syntax(code, offset + pos as u64);
+        loop {
+            let buf = &input[self.pos..];
+            let initial = match buf.first() {
+                Some(&initial) => initial,
+                None => return Ok(Some(1)),
+            };
+            let (major, info) = (initial >> 5, initial & 0x1f);
+            let arg_len = match info {
+                0..=23 | 31 => 0,
+                24 => 1,
+                25 => 2,
+                26 => 4,
+                27 => 8,
+                _ => return Err(error(self.pos, ErrorCode::UnassignedCode)),
+            };
+            let head_len = 1 + arg_len;
+            if buf.len() < head_len {
+                return Ok(Some(head_len - buf.len()));
+            }
+            let arg = if info < 24 {
+                u64::from(info)
+            } else {
+                buf[1..head_len]
+                    .iter()
+                    .fold(0, |arg, &byte| arg << 8 | u64::from(byte))
+            };
+            if let Some(&Frame::Chunks(chunk_major)) = self.stack.last() {
+                // The chunks of an indefinite-length string are definite-length strings of the
+                // same type.
+                if initial != 0xff && (major != chunk_major || info == 31) {
+                    return Err(error(self.pos, ErrorCode::UnexpectedCode));
+                }
+            }
+            match (major, info) {
+                (0, 31) | (1, 31) | (6, 31) => {
+                    return Err(error(self.pos, ErrorCode::UnassignedCode));
+                }
+                (2, 31) | (3, 31) => {
+                    self.pos += 1;
+                    self.stack.push(Frame::Chunks(major));
+                    continue;
+                }
+                (4, 31) | (5, 31) => {
+                    self.check_depth(offset)?;
+                    self.pos += 1;
+                    self.stack.push(Frame::Indefinite {
+                        map: major == 5,
+                        items: 0,
+                    });
+                    continue;
+                }
+                (7, 31) => {
+                    match self.stack.last() {
+                        Some(&Frame::Indefinite { map, items }) if !map || items % 2 == 0 => {}
+                        Some(&Frame::Chunks(_)) => {}
+                        _ => return Err(error(self.pos, ErrorCode::UnexpectedCode)),
+                    }
+                    self.stack.pop();
+                    self.pos += 1;
+                }
+                (2, _) | (3, _) => {
+                    let len = usize::try_from(arg)
+                        .map_err(|_| error(self.pos, ErrorCode::LengthOutOfRange))?;
+                    let available = buf.len() - head_len;
+                    if available < len {
+                        return Ok(Some(len - available));
+                    }
+                    self.pos += head_len + len;
+                }
+                (4, _) | (5, _) => {
+                    let items = if major == 5 {
+                        arg.checked_mul(2)
+                            .ok_or_else(|| error(self.pos, ErrorCode::LengthOutOfRange))?
+                    } else {
+                        arg
+                    };
+                    if items > 0 {
+                        self.check_depth(offset)?;
+                        self.pos += head_len;
+                        self.stack.push(Frame::Items(items));
+                        continue;
+                    }
+                    self.pos += head_len;
+                }
+                (6, _) => {
+                    self.check_depth(offset)?;
+                    self.pos += head_len;
+                    self.stack.push(Frame::Items(1));
+                    continue;
+                }
+                _ => self.pos += head_len,
+            }
+            if self.complete_item() {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Checks that another container can be entered at the current position.
+    fn check_depth(&self, offset: u64) -> Result<()> {
+        if self.stack.len() + 1 >= MAX_DEPTH {
+            return Err(Error::syntax(
+                ErrorCode::RecursionLimitExceeded,
+                offset + self.pos as u64,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Counts a completed item in the enclosing containers and returns true if it was the
+    /// top-level item.
+    fn complete_item(&mut self) -> bool {
+        loop {
+            match self.stack.last_mut() {
+                None => return true,
+                Some(Frame::Items(items)) => {
+                    *items -= 1;
+                    if *items > 0 {
+                        return false;
+                    }
+                    self.stack.pop();
+                }
+                Some(Frame::Indefinite { items, .. }) => {
+                    *items += 1;
+                    return false;
+                }
+                Some(Frame::Chunks(_)) => return false,
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Encoded CBOR data items that are decoded lazily.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use crate::error::{Error, ErrorCode, Result};
+
+pub(crate) const RAW_VALUE_NAME: &str = "\0cbor_raw_value";
+
+/// The encoded bytes of a single CBOR data item.
+///
+/// Deserializing a `RawValue` with a `serde_cbor` deserializer captures the exact bytes of the
+/// next data item instead of decoding it. Serializing it with a `serde_cbor` serializer writes
+/// the bytes back verbatim. This is useful to forward parts of a message unchanged or to decode
+/// them later.
+///
+/// In deterministic encoding mode the bytes are only written if they are in deterministic
+/// encoding already, as checked by `Deserializer::strict`. Otherwise serializing fails, so the
+/// output of `Serializer::canonical` is always deterministic.
+///
+/// Other data formats see a `RawValue` as a byte string.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_cbor::raw::RawValue;
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Envelope {
+///     to: String,
+///     body: RawValue,
+/// }
+///
+/// // {"to": "bob", "body": [1, 2]}
+/// let bytes = b"\xa2\x62to\x63bob\x64body\x82\x01\x02";
+/// let envelope: Envelope = serde_cbor::from_slice(bytes).unwrap();
+/// assert_eq!(envelope.body.as_bytes(), b"\x82\x01\x02");
+///
+/// let body: Vec<u8> = envelope.body.deserialize_into().unwrap();
+/// assert_eq!(body, [1, 2]);
+/// assert_eq!(serde_cbor::to_vec(&envelope).unwrap(), &bytes[..]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct RawValue {
+    bytes: Vec<u8>,
+}
+
+impl RawValue {
+    /// Wraps the encoding of a single data item.
+    ///
+    /// Returns an error if `bytes` isn't exactly one well-formed data item.
+    pub fn from_vec(bytes: Vec<u8>) -> Result<RawValue> {
+        let len = crate::de::validate(&bytes)?;
+        if len != bytes.len() {
+            return Err(Error::syntax(ErrorCode::TrailingData, len as u64 + 1));
+        }
+        Ok(RawValue { bytes })
+    }
+
+    /// Returns the encoded bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the encoded bytes, consuming the `RawValue`.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Decodes the data item as an instance of type `T`.
+    pub fn deserialize_into<'a, T>(&'a self) -> Result<T>
+    where
+        T: Deserialize<'a>,
+    {
+        crate::from_slice(&self.bytes)
+    }
+}
+
+impl fmt::Debug for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RawValue(")?;
+        for byte in &self.bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+        f.write_str(")")
+    }
+}
+
+pub(crate) struct RawBytes<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Serialize for RawBytes<'a> {
+    fn serialize<S: Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
+        s.serialize_bytes(self.0)
+    }
+}
+
+impl Serialize for RawValue {
+    fn serialize<S: Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
+        s.serialize_newtype_struct(RAW_VALUE_NAME, &RawBytes(&self.bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> core::result::Result<Self, D::Error> {
+        struct RawValueVisitor;
+
+        impl<'de> de::Visitor<'de> for RawValueVisitor {
+            type Value = RawValue;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("a CBOR data item")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> core::result::Result<RawValue, E> {
+                Ok(RawValue { bytes: v.to_vec() })
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> core::result::Result<RawValue, E> {
+                Ok(RawValue { bytes: v })
+            }
+        }
+
+        d.deserialize_newtype_struct(RAW_VALUE_NAME, RawValueVisitor)
+    }
+}
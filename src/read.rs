@@ -1,12 +1,15 @@
 #[cfg(feature = "alloc")]
 use alloc::{vec, vec::Vec};
-#[cfg(feature = "std")]
+#[cfg(any(feature = "std", feature = "alloc"))]
 use core::cmp;
 use core::mem;
 
 #[cfg(feature = "std")]
 use std::io::{self, Read as StdRead};
 
+#[cfg(all(feature = "std", feature = "bytes"))]
+use bytes::Bytes;
+
 use crate::error::{Error, ErrorCode, Result};
 
 #[cfg(not(feature = "unsealed_read_write"))]
@@ -14,6 +17,9 @@ use crate::error::{Error, ErrorCode, Result};
 ///
 /// This trait is sealed by default, enabling the `unsealed_read_write` feature removes this bound
 /// to allow objects outside of this crate to implement this trait.
+///
+/// To deserialize from input types outside of this crate, implement the stable
+/// [`Source`](trait.Source.html) trait and wrap it in a [`SourceRead`](struct.SourceRead.html).
 pub trait Read<'de>: private::Sealed {
     #[doc(hidden)]
     /// Read n bytes from the input.
@@ -61,10 +67,25 @@ pub trait Read<'de>: private::Sealed {
 
     #[doc(hidden)]
     fn offset(&self) -> u64;
+
+    #[doc(hidden)]
+    #[cfg(all(feature = "std", feature = "bytes"))]
+    fn shared_bytes(&self, _slice: &'de [u8]) -> Option<Bytes> {
+        None
+    }
+
+    #[doc(hidden)]
+    fn input_since(&self, _offset: u64) -> Option<&'de [u8]> {
+        None
+    }
 }
 
 #[cfg(feature = "unsealed_read_write")]
 /// Trait used by the deserializer for iterating over input.
+///
+/// The methods of this trait may change between versions. To deserialize from input types outside
+/// of this crate, prefer implementing the stable [`Source`](trait.Source.html) trait and wrapping
+/// it in a [`SourceRead`](struct.SourceRead.html).
 pub trait Read<'de> {
     /// Read n bytes from the input.
     ///
@@ -113,6 +134,18 @@ pub trait Read<'de> {
 
     /// Returns the offset from the start of the reader.
     fn offset(&self) -> u64;
+
+    /// Returns a slice returned by `read` as `Bytes` that share the buffer of the input, if the
+    /// input is a `Bytes`.
+    #[cfg(all(feature = "std", feature = "bytes"))]
+    fn shared_bytes(&self, _slice: &'de [u8]) -> Option<Bytes> {
+        None
+    }
+
+    /// Returns the input from `offset` up to the current position, if it is borrowed for `'de`.
+    fn input_since(&self, _offset: u64) -> Option<&'de [u8]> {
+        None
+    }
 }
 
 /// Represents a reader that can return its current position
@@ -403,6 +436,205 @@ impl<'a> Read<'a> for SliceRead<'a> {
     fn offset(&self) -> u64 {
         self.index as u64
     }
+
+    fn input_since(&self, offset: u64) -> Option<&'a [u8]> {
+        self.slice.get(offset as usize..self.index)
+    }
+}
+
+/// A CBOR input source that reads from `Bytes`.
+///
+/// Byte strings deserialized into a [`SharedBytes`](../shared/struct.SharedBytes.html) share
+/// the buffer of the input instead of being copied. Otherwise this works like `SliceRead`.
+#[cfg(all(feature = "std", feature = "bytes"))]
+#[derive(Debug)]
+pub struct BytesRead<'a> {
+    bytes: &'a Bytes,
+    read: SliceRead<'a>,
+}
+
+#[cfg(all(feature = "std", feature = "bytes"))]
+impl<'a> BytesRead<'a> {
+    /// Creates a CBOR input source to read from `Bytes`.
+    pub fn new(bytes: &'a Bytes) -> BytesRead<'a> {
+        BytesRead {
+            bytes,
+            read: SliceRead::new(bytes),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "bytes"))]
+impl<'a> Offset for BytesRead<'a> {
+    #[inline]
+    fn byte_offset(&self) -> usize {
+        self.read.byte_offset()
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "bytes",
+    not(feature = "unsealed_read_write")
+))]
+impl<'a> private::Sealed for BytesRead<'a> {}
+
+#[cfg(all(feature = "std", feature = "bytes"))]
+impl<'a> Read<'a> for BytesRead<'a> {
+    #[inline]
+    fn next(&mut self) -> Result<Option<u8>> {
+        self.read.next()
+    }
+
+    #[inline]
+    fn peek(&mut self) -> Result<Option<u8>> {
+        self.read.peek()
+    }
+
+    fn clear_buffer(&mut self) {
+        self.read.clear_buffer()
+    }
+
+    fn read_to_buffer(&mut self, n: usize) -> Result<()> {
+        self.read.read_to_buffer(n)
+    }
+
+    #[inline]
+    fn read<'b>(&'b mut self, n: usize) -> Result<EitherLifetime<'b, 'a>> {
+        self.read.read(n)
+    }
+
+    fn take_buffer<'b>(&'b mut self) -> EitherLifetime<'b, 'a> {
+        self.read.take_buffer()
+    }
+
+    #[inline]
+    fn read_into(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.read.read_into(buf)
+    }
+
+    #[inline]
+    fn discard(&mut self) {
+        self.read.discard()
+    }
+
+    fn offset(&self) -> u64 {
+        self.read.offset()
+    }
+
+    fn input_since(&self, offset: u64) -> Option<&'a [u8]> {
+        self.read.input_since(offset)
+    }
+
+    fn shared_bytes(&self, slice: &'a [u8]) -> Option<Bytes> {
+        Some(self.bytes.slice_ref(slice))
+    }
+}
+
+/// A CBOR input source that reads from a slice of bytes, but only hands out borrows that live as
+/// long as the reader is borrowed.
+///
+/// Unlike `SliceRead` this implements `Read<'de>` for every lifetime `'de`, which allows to
+/// deserialize data recorded into a temporary buffer with the seed of the outer deserializer.
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Debug)]
+pub(crate) struct BufferRead<'a> {
+    slice: &'a [u8],
+    scratch: Vec<u8>,
+    index: usize,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'a> BufferRead<'a> {
+    pub(crate) fn new(slice: &'a [u8]) -> BufferRead<'a> {
+        BufferRead {
+            slice,
+            scratch: vec![],
+            index: 0,
+        }
+    }
+
+    fn end(&self, n: usize) -> Result<usize> {
+        match self.index.checked_add(n) {
+            Some(end) if end <= self.slice.len() => Ok(end),
+            _ => Err(Error::syntax(
+                ErrorCode::EofWhileParsingValue,
+                self.slice.len() as u64,
+            )),
+        }
+    }
+}
+
+#[cfg(all(
+    any(feature = "std", feature = "alloc"),
+    not(feature = "unsealed_read_write")
+))]
+impl<'a> private::Sealed for BufferRead<'a> {}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'de, 'a> Read<'de> for BufferRead<'a> {
+    #[inline]
+    fn next(&mut self) -> Result<Option<u8>> {
+        // This is duplicated from SliceRead, can that be eased?
+        Ok(if self.index < self.slice.len() {
+            let ch = self.slice[self.index];
+            self.index += 1;
+            Some(ch)
+        } else {
+            None
+        })
+    }
+
+    #[inline]
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(if self.index < self.slice.len() {
+            Some(self.slice[self.index])
+        } else {
+            None
+        })
+    }
+
+    fn clear_buffer(&mut self) {
+        self.scratch.clear();
+    }
+
+    fn read_to_buffer(&mut self, n: usize) -> Result<()> {
+        let end = self.end(n)?;
+        let slice = &self.slice[self.index..end];
+        self.scratch.extend_from_slice(slice);
+        self.index = end;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn read<'b>(&'b mut self, n: usize) -> Result<EitherLifetime<'b, 'de>> {
+        let end = self.end(n)?;
+        let slice = &self.slice[self.index..end];
+        self.index = end;
+        Ok(EitherLifetime::Short(slice))
+    }
+
+    fn take_buffer<'b>(&'b mut self) -> EitherLifetime<'b, 'de> {
+        EitherLifetime::Short(&self.scratch)
+    }
+
+    #[inline]
+    fn read_into(&mut self, buf: &mut [u8]) -> Result<()> {
+        let end = self.end(buf.len())?;
+        buf.copy_from_slice(&self.slice[self.index..end]);
+        self.index = end;
+        Ok(())
+    }
+
+    #[inline]
+    fn discard(&mut self) {
+        self.index += 1;
+    }
+
+    fn offset(&self) -> u64 {
+        self.index as u64
+    }
 }
 
 /// A CBOR input source that reads from a slice of bytes using a fixed size scratch buffer.
@@ -512,6 +744,10 @@ impl<'a, 'b> Read<'a> for SliceReadFixed<'a, 'b> {
     fn offset(&self) -> u64 {
         self.index as u64
     }
+
+    fn input_since(&self, offset: u64) -> Option<&'a [u8]> {
+        self.slice.get(offset as usize..self.index)
+    }
 }
 
 #[cfg(any(feature = "std", feature = "alloc"))]
@@ -635,3 +871,232 @@ impl<'a> Read<'a> for MutSliceRead<'a> {
         (self.before + self.index) as u64
     }
 }
+
+/// A buffered source of CBOR input that can be implemented outside of this crate.
+///
+/// Unlike `Read` this trait is stable and doesn't need the `unsealed_read_write` feature. It is
+/// similar to `std::io::BufRead`: the deserializer looks at the bytes returned by `fill_buf` and
+/// marks the ones it used with `consume`. This fits inputs like ring buffers, memory-mapped
+/// regions or DMA buffers that hand out their data in contiguous chunks.
+///
+/// Wrap a source in a [`SourceRead`](struct.SourceRead.html) to deserialize from it, or use
+/// [`from_source`](../de/fn.from_source.html).
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_cbor::de::Source;
+///
+/// /// A source that hands out its input in chunks of at most two bytes.
+/// struct Chunked<'a>(&'a [u8]);
+///
+/// impl<'a> Source for Chunked<'a> {
+///     type Error = serde_cbor::Error;
+///
+///     fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+///         Ok(&self.0[..self.0.len().min(2)])
+///     }
+///
+///     fn consume(&mut self, n: usize) {
+///         self.0 = &self.0[n..];
+///     }
+/// }
+///
+/// let value: String = serde_cbor::de::from_source(Chunked(b"\x65hello")).unwrap();
+/// assert_eq!(value, "hello");
+/// ```
+pub trait Source {
+    /// The type of error returned when the source fails.
+    ///
+    /// Sources without a suitable error type can use `serde_cbor::Error` and create errors with
+    /// `serde::de::Error::custom`.
+    type Error: Into<Error>;
+
+    /// Returns the next bytes of the input, reading more if none are available.
+    ///
+    /// An empty slice signals the end of the input.
+    fn fill_buf(&mut self) -> core::result::Result<&[u8], Self::Error>;
+
+    /// Marks `n` bytes at the start of the slice returned by `fill_buf` as consumed.
+    ///
+    /// `n` is never larger than the length of that slice.
+    fn consume(&mut self, n: usize);
+}
+
+impl Source for &[u8] {
+    type Error = Error;
+
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        Ok(*self)
+    }
+
+    fn consume(&mut self, n: usize) {
+        *self = &self[n..];
+    }
+}
+
+impl<S> Source for &mut S
+where
+    S: Source + ?Sized,
+{
+    type Error = S::Error;
+
+    fn fill_buf(&mut self) -> core::result::Result<&[u8], Self::Error> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, n: usize) {
+        (**self).consume(n)
+    }
+}
+
+/// A CBOR input source that reads from a [`Source`](trait.Source.html).
+///
+/// Byte and text strings are copied into a scratch buffer, so the deserializer can't borrow from
+/// the source.
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Debug)]
+pub struct SourceRead<S> {
+    source: S,
+    scratch: Vec<u8>,
+    offset: u64,
+    /// Set if the first byte of the source was returned by `peek` but not consumed yet.
+    peeked: bool,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<S> SourceRead<S>
+where
+    S: Source,
+{
+    /// Creates a CBOR input source to read from a `Source`.
+    pub fn new(source: S) -> SourceRead<S> {
+        SourceRead {
+            source,
+            scratch: vec![],
+            offset: 0,
+            peeked: false,
+        }
+    }
+
+    /// Returns a reference to the underlying source.
+    pub fn get_ref(&self) -> &S {
+        &self.source
+    }
+
+    /// Returns a mutable reference to the underlying source.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Unwraps the source. Bytes that have been peeked at are still in the source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    fn first(&mut self) -> Result<Option<u8>> {
+        let buf = self.source.fill_buf().map_err(Into::into)?;
+        Ok(buf.first().cloned())
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.source.consume(n);
+        self.offset += n as u64;
+        self.peeked = false;
+    }
+
+    /// Passes the next `n` bytes of the source to `f` in chunks.
+    fn transfer<F>(&mut self, mut n: usize, mut f: F) -> Result<()>
+    where
+        F: FnMut(&[u8]),
+    {
+        while n > 0 {
+            let len = {
+                let buf = self.source.fill_buf().map_err(Into::into)?;
+                if buf.is_empty() {
+                    return Err(Error::syntax(ErrorCode::EofWhileParsingValue, self.offset));
+                }
+                let len = cmp::min(n, buf.len());
+                f(&buf[..len]);
+                len
+            };
+            self.advance(len);
+            n -= len;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(
+    any(feature = "std", feature = "alloc"),
+    not(feature = "unsealed_read_write")
+))]
+impl<S> private::Sealed for SourceRead<S> where S: Source {}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'de, S> Read<'de> for SourceRead<S>
+where
+    S: Source,
+{
+    #[inline]
+    fn next(&mut self) -> Result<Option<u8>> {
+        let byte = self.first()?;
+        if byte.is_some() {
+            self.advance(1);
+        }
+        Ok(byte)
+    }
+
+    #[inline]
+    fn peek(&mut self) -> Result<Option<u8>> {
+        let byte = self.first()?;
+        self.peeked = byte.is_some();
+        Ok(byte)
+    }
+
+    fn clear_buffer(&mut self) {
+        self.scratch.clear();
+    }
+
+    fn read_to_buffer(&mut self, n: usize) -> Result<()> {
+        // defend against malicious input pretending to be huge strings by limiting growth
+        self.scratch.reserve(cmp::min(n, 16 * 1024));
+        let mut scratch = mem::take(&mut self.scratch);
+        let result = self.transfer(n, |chunk| scratch.extend_from_slice(chunk));
+        self.scratch = scratch;
+        result
+    }
+
+    fn take_buffer<'a>(&'a mut self) -> EitherLifetime<'a, 'de> {
+        EitherLifetime::Short(&self.scratch)
+    }
+
+    fn read_into(&mut self, buf: &mut [u8]) -> Result<()> {
+        let mut filled = 0;
+        self.transfer(buf.len(), |chunk| {
+            buf[filled..filled + chunk.len()].copy_from_slice(chunk);
+            filled += chunk.len();
+        })
+    }
+
+    #[inline]
+    fn discard(&mut self) {
+        if self.peeked {
+            self.advance(1);
+        }
+    }
+
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<S> Offset for SourceRead<S>
+where
+    S: Source,
+{
+    fn byte_offset(&self) -> usize {
+        self.offset as usize
+    }
+}
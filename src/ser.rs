@@ -3,9 +3,13 @@
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use crate::write::BufWrite;
 #[cfg(feature = "std")]
 pub use crate::write::IoWrite;
-pub use crate::write::{SliceWrite, Write};
+pub use crate::write::{Sink, SinkWrite, SliceWrite, Write};
+
+use crate::write::CountingWrite;
 
 use crate::error::{Error, Result};
 use half::f16;
@@ -13,7 +17,20 @@ use serde::ser::{self, Serialize};
 #[cfg(feature = "std")]
 use std::io;
 
-use crate::tags::{get_tag, CBOR_NEWTYPE_NAME};
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::embedded::CBOR_EMBEDDED_NAME;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::raw::RAW_VALUE_NAME;
+use crate::tags::{tag_digit, CBOR_NEWTYPE_NAME, TAG_DIGITS};
+
+/// The name of the newtype struct that simple values are serialized as. It contains the number
+/// of the simple value as an `u8`.
+pub(crate) const CBOR_SIMPLE_NAME: &str = "\0cbor_simple";
+
+/// The names of the newtype structs that bignums are serialized as. They contain the magnitude
+/// of the bignum as a byte string.
+pub(crate) const CBOR_BIGNUM_NAME: &str = "\0cbor_bignum";
+pub(crate) const CBOR_NEGATIVE_BIGNUM_NAME: &str = "\0cbor_negative_bignum";
 
 /// Serializes a value to a vector.
 #[cfg(any(feature = "std", feature = "alloc"))]
@@ -37,6 +54,19 @@ where
     Ok(vec)
 }
 
+/// Serializes a value to a vector using deterministic encoding.
+///
+/// See [`Serializer::canonical`](struct.Serializer.html#method.canonical) for details.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn to_vec_canonical<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: ser::Serialize,
+{
+    let mut vec = Vec::new();
+    value.serialize(&mut Serializer::new(&mut vec).canonical())?;
+    Ok(vec)
+}
+
 /// Serializes a value to a writer.
 #[cfg(feature = "std")]
 pub fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
@@ -47,12 +77,63 @@ where
     value.serialize(&mut Serializer::new(&mut IoWrite::new(writer)))
 }
 
+/// Serializes a value to a [`Sink`](trait.Sink.html).
+pub fn to_sink<S, T>(sink: S, value: &T) -> Result<()>
+where
+    S: Sink,
+    T: ser::Serialize,
+{
+    value.serialize(&mut Serializer::new(SinkWrite::new(sink)))
+}
+
+/// Encodes the shortest head of a data item into `buf` and returns its length.
+#[inline]
+fn encode_head(buf: &mut [u8; 9], major: u8, value: u64) -> usize {
+    let len = match value {
+        0..=0x17 => 0,
+        0x18..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x1_0000..=0xffff_ffff => 4,
+        _ => 8,
+    };
+    let info = match len {
+        0 => value as u8,
+        1 => 24,
+        2 => 25,
+        4 => 26,
+        _ => 27,
+    };
+    buf[0] = major << 5 | info;
+    buf[1..=len].copy_from_slice(&value.to_be_bytes()[8 - len..]);
+    len + 1
+}
+
+/// The order of map keys when serializing in canonical mode.
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeyOrder {
+    /// Bytewise lexicographic order of the encoded keys (RFC 8949, section 4.2.1).
+    Bytewise,
+    /// Shorter encoded keys sort first, keys of equal length bytewise (RFC 7049, section 3.9).
+    LengthFirst,
+}
+
 /// A structure for serializing Rust values to CBOR.
 #[derive(Debug)]
 pub struct Serializer<W> {
     writer: W,
     packed: bool,
     enum_as_map: bool,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    canonical: Option<KeyOrder>,
+    /// Set while serializing a `RawValue`, whose bytes are written verbatim.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    raw: bool,
+    /// Set while serializing a simple value, which is passed as an `u8`.
+    simple: bool,
+    /// The digits of a tag read so far and their number, set while serializing the digits of a
+    /// tagged value.
+    tag: Option<(u64, u32)>,
 }
 
 impl<W> Serializer<W>
@@ -68,6 +149,12 @@ where
             writer,
             packed: false,
             enum_as_map: true,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            canonical: None,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            raw: false,
+            simple: false,
+            tag: None,
         }
     }
 
@@ -120,6 +207,47 @@ where
         self
     }
 
+    /// Enable deterministic encoding as described in RFC 8949, section 4.2.
+    ///
+    /// Integers, lengths and floats always use their shortest form. In addition the canonical
+    /// mode buffers every map and struct and writes its entries sorted by the bytewise
+    /// lexicographic order of their encoded keys, and sequences of unknown length are buffered
+    /// to be written with a definite length. Thus two equal values always serialize to the same
+    /// bytes, even if the order of map entries differs (like in a `HashMap`). Maps with
+    /// duplicate keys are rejected with an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use serde_cbor::Serializer;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert("b", 2);
+    /// map.insert("a", 1);
+    ///
+    /// let mut vec = Vec::new();
+    /// map.serialize(&mut Serializer::new(&mut vec).canonical()).unwrap();
+    /// assert_eq!(vec, b"\xa2\x61a\x01\x61b\x02");
+    /// ```
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn canonical(mut self) -> Self {
+        self.canonical = Some(KeyOrder::Bytewise);
+        self
+    }
+
+    /// Enable deterministic encoding with the key order of RFC 7049.
+    ///
+    /// This works like [`canonical`](#method.canonical) but sorts map keys by the length of
+    /// their encoding first and only compares keys of the same length bytewise. This is the
+    /// order used by `Value` and some older CBOR implementations.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn legacy_canonical(mut self) -> Self {
+        self.canonical = Some(KeyOrder::LengthFirst);
+        self
+    }
+
     /// Writes a CBOR self-describe tag to the stream.
     ///
     /// Tagging allows a decoder to distinguish different file formats based on their content
@@ -181,12 +309,108 @@ where
         }
     }
 
+    /// Writes the head of a byte or text string and its contents with a single vectored write.
+    #[inline]
+    fn write_string(&mut self, major: u8, value: &[u8]) -> Result<()> {
+        let mut head = [0; 9];
+        let len = encode_head(&mut head, major, value.len() as u64);
+        self.writer
+            .write_all_vectored(&[&head[..len], value])
+            .map_err(|e| e.into())
+    }
+
+    /// Writes an integer that doesn't fit into 64 bits as a bignum with the given tag.
+    fn write_bignum(&mut self, tag: u64, magnitude: u128) -> Result<()> {
+        let bytes = magnitude.to_be_bytes();
+        let start = magnitude.leading_zeros() as usize / 8;
+        self.write_u64(6, tag)?;
+        self.write_string(2, &bytes[start..])
+    }
+
+    /// Serializes a value with the options of this serializer into a new buffer.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn to_buffer<T>(&self, value: &T) -> Result<Vec<u8>>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let mut ser = Serializer {
+            writer: Vec::new(),
+            packed: self.packed,
+            enum_as_map: self.enum_as_map,
+            canonical: self.canonical,
+            raw: false,
+            simple: false,
+            tag: None,
+        };
+        value.serialize(&mut ser)?;
+        Ok(ser.writer)
+    }
+
+    /// Replaces the writer of this serializer, keeping its options.
+    fn map_writer<V, F>(self, f: F) -> Serializer<V>
+    where
+        F: FnOnce(W) -> V,
+    {
+        Serializer {
+            writer: f(self.writer),
+            packed: self.packed,
+            enum_as_map: self.enum_as_map,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            canonical: self.canonical,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            raw: false,
+            simple: false,
+            tag: None,
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn write_sorted_map(&mut self, mut entries: Vec<(Vec<u8>, Vec<u8>)>) -> Result<()> {
+        match self.canonical {
+            Some(KeyOrder::LengthFirst) => {
+                entries.sort_by(|a, b| a.0.len().cmp(&b.0.len()).then_with(|| a.0.cmp(&b.0)))
+            }
+            _ => entries.sort_by(|a, b| a.0.cmp(&b.0)),
+        }
+        // Equal keys are adjacent after sorting.
+        if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(Error::message("Map keys must be unique in canonical mode"));
+        }
+        self.write_u64(5, entries.len() as u64)?;
+        for (key, value) in entries {
+            self.writer.write_all(&key).map_err(|e| e.into())?;
+            self.writer.write_all(&value).map_err(|e| e.into())?;
+        }
+        Ok(())
+    }
+
     #[inline]
     fn serialize_collection<'a>(
         &'a mut self,
         major: u8,
         len: Option<usize>,
     ) -> Result<CollectionSerializer<'a, W>> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.canonical.is_some() {
+                let buffer = match (major, len) {
+                    (5, _) => Some(Buffer::Map(Vec::with_capacity(len.unwrap_or(0)))),
+                    (_, None) => Some(Buffer::Seq {
+                        len: 0,
+                        bytes: Vec::new(),
+                    }),
+                    _ => None,
+                };
+                if buffer.is_some() {
+                    return Ok(CollectionSerializer {
+                        ser: self,
+                        needs_eof: false,
+                        buffer,
+                    });
+                }
+            }
+        }
+
         let needs_eof = match len {
             Some(len) => {
                 self.write_u64(major, len as u64)?;
@@ -203,6 +427,8 @@ where
         Ok(CollectionSerializer {
             ser: self,
             needs_eof,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            buffer: None,
         })
     }
 }
@@ -268,12 +494,12 @@ where
     fn serialize_i128(self, value: i128) -> Result<()> {
         if value < 0 {
             if -(value + 1) > i128::from(u64::max_value()) {
-                return Err(Error::message("The number can't be stored in CBOR"));
+                return self.write_bignum(3, -(value + 1) as u128);
             }
             self.write_u64(1, -(value + 1) as u64)
         } else {
             if value > i128::from(u64::max_value()) {
-                return Err(Error::message("The number can't be stored in CBOR"));
+                return self.write_bignum(2, value as u128);
             }
             self.write_u64(0, value as u64)
         }
@@ -281,6 +507,13 @@ where
 
     #[inline]
     fn serialize_u8(self, value: u8) -> Result<()> {
+        if self.simple {
+            self.simple = false;
+            if (0x18..0x20).contains(&value) {
+                return Err(Error::message("Simple values 24 to 31 are reserved"));
+            }
+            return self.write_u8(7, value);
+        }
         self.write_u8(0, value)
     }
 
@@ -302,7 +535,7 @@ where
     #[inline]
     fn serialize_u128(self, value: u128) -> Result<()> {
         if value > u128::from(u64::max_value()) {
-            return Err(Error::message("The number can't be stored in CBOR"));
+            return self.write_bignum(2, value);
         }
         self.write_u64(0, value as u64)
     }
@@ -351,16 +584,22 @@ where
 
     #[inline]
     fn serialize_str(self, value: &str) -> Result<()> {
-        self.write_u64(3, value.len() as u64)?;
-        self.writer
-            .write_all(value.as_bytes())
-            .map_err(|e| e.into())
+        self.write_string(3, value.as_bytes())
     }
 
     #[inline]
     fn serialize_bytes(self, value: &[u8]) -> Result<()> {
-        self.write_u64(2, value.len() as u64)?;
-        self.writer.write_all(value).map_err(|e| e.into())
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.raw {
+                self.raw = false;
+                if let Some(order) = self.canonical {
+                    crate::de::check_deterministic(value, order == KeyOrder::LengthFirst)?;
+                }
+                return self.writer.write_all(value).map_err(|e| e.into());
+            }
+        }
+        self.write_string(2, value)
     }
 
     #[inline]
@@ -406,8 +645,43 @@ where
         T: ?Sized + ser::Serialize,
     {
         if name == CBOR_NEWTYPE_NAME {
-            for tag in get_tag().into_iter() {
-                self.write_u64(6, tag)?;
+            self.tag = Some((0, 0));
+            return value.serialize(self);
+        }
+        if let (Some((tag, digits)), Some(digit)) = (self.tag, tag_digit(name)) {
+            let tag = tag << 4 | digit;
+            if digits + 1 < TAG_DIGITS {
+                self.tag = Some((tag, digits + 1));
+            } else {
+                // All digits are known, the value follows.
+                self.tag = None;
+                if cfg!(feature = "tags") {
+                    self.write_u64(6, tag)?;
+                }
+            }
+            return value.serialize(self);
+        }
+        if name == CBOR_BIGNUM_NAME {
+            self.write_u64(6, 2)?;
+        } else if name == CBOR_NEGATIVE_BIGNUM_NAME {
+            self.write_u64(6, 3)?;
+        }
+        if name == CBOR_SIMPLE_NAME {
+            self.simple = true;
+            let r = value.serialize(&mut *self);
+            self.simple = false;
+            return r;
+        }
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if name == CBOR_EMBEDDED_NAME {
+                self.write_u64(6, 24)?;
+            }
+            if name == RAW_VALUE_NAME {
+                self.raw = true;
+                let r = value.serialize(&mut *self);
+                self.raw = false;
+                return r;
             }
         }
         value.serialize(self)
@@ -493,8 +767,23 @@ where
 
     #[inline]
     fn serialize_struct(self, _name: &'static str, len: usize) -> Result<StructSerializer<'a, W>> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.canonical.is_some() {
+                return Ok(StructSerializer {
+                    ser: self,
+                    idx: 0,
+                    entries: Some(Vec::with_capacity(len)),
+                });
+            }
+        }
         self.write_u64(5, len as u64)?;
-        Ok(StructSerializer { ser: self, idx: 0 })
+        Ok(StructSerializer {
+            ser: self,
+            idx: 0,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            entries: None,
+        })
     }
 
     #[inline]
@@ -587,6 +876,9 @@ where
 pub struct StructSerializer<'a, W> {
     ser: &'a mut Serializer<W>,
     idx: u32,
+    /// Encoded fields waiting to be sorted in canonical mode.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    entries: Option<Vec<(Vec<u8>, Vec<u8>)>>,
 }
 
 impl<'a, W> StructSerializer<'a, W>
@@ -598,6 +890,19 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if let Some(ref mut entries) = self.entries {
+                let key = if self.ser.packed {
+                    self.ser.to_buffer(&self.idx)?
+                } else {
+                    self.ser.to_buffer(key)?
+                };
+                entries.push((key, self.ser.to_buffer(value)?));
+                self.idx += 1;
+                return Ok(());
+            }
+        }
         if self.ser.packed {
             self.idx.serialize(&mut *self.ser)?;
         } else {
@@ -616,6 +921,12 @@ where
 
     #[inline]
     fn end_inner(self) -> Result<()> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if let Some(entries) = self.entries {
+                return self.ser.write_sorted_map(entries);
+            }
+        }
         Ok(())
     }
 }
@@ -672,10 +983,21 @@ where
     }
 }
 
+/// Items of a collection that are buffered in canonical mode.
+#[cfg(any(feature = "std", feature = "alloc"))]
+enum Buffer {
+    /// A sequence of unknown length, written once the number of elements is known.
+    Seq { len: u64, bytes: Vec<u8> },
+    /// The encoded entries of a map, written in sorted order.
+    Map(Vec<(Vec<u8>, Vec<u8>)>),
+}
+
 #[doc(hidden)]
 pub struct CollectionSerializer<'a, W> {
     ser: &'a mut Serializer<W>,
     needs_eof: bool,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    buffer: Option<Buffer>,
 }
 
 impl<'a, W> CollectionSerializer<'a, W>
@@ -684,6 +1006,17 @@ where
 {
     #[inline]
     fn end_inner(self) -> Result<()> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            match self.buffer {
+                Some(Buffer::Seq { len, bytes }) => {
+                    self.ser.write_u64(4, len)?;
+                    return self.ser.writer.write_all(&bytes).map_err(|e| e.into());
+                }
+                Some(Buffer::Map(entries)) => return self.ser.write_sorted_map(entries),
+                None => {}
+            }
+        }
         if self.needs_eof {
             self.ser.writer.write_all(&[0xff]).map_err(|e| e.into())
         } else {
@@ -704,6 +1037,18 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if let Some(Buffer::Seq {
+                ref mut len,
+                ref mut bytes,
+            }) = self.buffer
+            {
+                bytes.extend_from_slice(&self.ser.to_buffer(value)?);
+                *len += 1;
+                return Ok(());
+            }
+        }
         value.serialize(&mut *self.ser)
     }
 
@@ -725,6 +1070,13 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if let Some(Buffer::Map(ref mut entries)) = self.buffer {
+                entries.push((self.ser.to_buffer(key)?, Vec::new()));
+                return Ok(());
+            }
+        }
         key.serialize(&mut *self.ser)
     }
 
@@ -733,6 +1085,16 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if let Some(Buffer::Map(ref mut entries)) = self.buffer {
+                let entry = entries
+                    .last_mut()
+                    .expect("serialize_value called before serialize_key");
+                entry.1 = self.ser.to_buffer(value)?;
+                return Ok(());
+            }
+        }
         value.serialize(&mut *self.ser)
     }
 
@@ -741,3 +1103,164 @@ where
         self.end_inner()
     }
 }
+
+/// A serializer for CBOR sequences (RFC 8742).
+///
+/// A CBOR sequence is a concatenation of data items without any framing, as used by the media
+/// type `application/cbor-seq`. Each call to [`serialize`](#method.serialize) appends one data
+/// item to the writer, so a log file can be extended item by item and read back with
+/// [`Deserializer::into_iter`](../de/struct.Deserializer.html#method.into_iter).
+///
+/// The serializer counts the items and the bytes it has written, and remembers the offset at
+/// which each item starts. [`finish_with_index`](#method.finish_with_index) appends these
+/// offsets as a tagged index, which lets readers seek to single items. Alternatively
+/// [`into_parts`](#method.into_parts) returns them to be stored next to the sequence.
+///
+/// If serializing a value fails the item may be written partially, which leaves the sequence
+/// unreadable from that point on.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_cbor::{Deserializer, SequenceSerializer, Value};
+///
+/// let mut seq = SequenceSerializer::new(Vec::new());
+/// seq.serialize("first").unwrap();
+/// seq.serialize(&[1, 2, 3]).unwrap();
+/// assert_eq!(seq.len(), 2);
+/// assert_eq!(seq.offsets(), &[0, 6]);
+/// let bytes = seq.into_inner();
+///
+/// let mut items = Deserializer::from_slice(&bytes).into_iter::<Value>();
+/// assert_eq!(items.next().unwrap().unwrap(), Value::Text("first".to_string()));
+/// assert!(items.next().unwrap().is_ok());
+/// assert!(items.next().is_none());
+/// ```
+#[derive(Debug)]
+pub struct SequenceSerializer<W> {
+    ser: Serializer<CountingWrite<W>>,
+    len: u64,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    offsets: Vec<u64>,
+}
+
+impl<W> SequenceSerializer<W>
+where
+    W: Write,
+{
+    /// The media type of CBOR sequences.
+    pub const MEDIA_TYPE: &'static str = "application/cbor-seq";
+
+    /// The tag of the index written by [`finish_with_index`](#method.finish_with_index).
+    ///
+    /// This is the ASCII string "cbix" as a number. It is not registered with IANA.
+    pub const INDEX_TAG: u64 = 0x6362_6978;
+
+    /// Creates a new sequence serializer that writes items with the default options.
+    pub fn new(writer: W) -> Self {
+        SequenceSerializer::from_serializer(Serializer::new(writer))
+    }
+
+    /// Creates a new sequence serializer that writes items with the options of `ser`.
+    ///
+    /// ```rust
+    /// use serde_cbor::{SequenceSerializer, Serializer};
+    ///
+    /// let ser = Serializer::new(Vec::new()).packed_format();
+    /// let mut seq = SequenceSerializer::from_serializer(ser);
+    /// seq.serialize(&1).unwrap();
+    /// ```
+    pub fn from_serializer(ser: Serializer<W>) -> Self {
+        SequenceSerializer {
+            ser: ser.map_writer(CountingWrite::new),
+            len: 0,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Appends a value to the sequence.
+    pub fn serialize<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let _offset = self.ser.writer.bytes_written();
+        value.serialize(&mut self.ser)?;
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        self.offsets.push(_offset);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the number of items written.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no items have been written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes written.
+    pub fn bytes_written(&self) -> u64 {
+        self.ser.writer.bytes_written()
+    }
+
+    /// Returns the byte offset at which each item starts.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn offsets(&self) -> &[u64] {
+        &self.offsets
+    }
+
+    /// Appends the offsets of all items as an index and returns the writer.
+    ///
+    /// The index is an array of unsigned integers tagged with
+    /// [`INDEX_TAG`](#associatedconstant.INDEX_TAG), which tells it apart from the items. It is
+    /// the last item of the sequence and doesn't contain its own offset. Readers that iterate
+    /// over the sequence with `Deserializer::into_iter` recognize it by its first bytes
+    /// `0xda 0x63 0x62 0x69 0x78`, the encoded tag, at the
+    /// [`byte_offset`](../de/struct.StreamDeserializer.html#method.byte_offset) of the next item.
+    /// With the `tags` feature a `Value` also shows the index as
+    /// `Value::Tag(SequenceSerializer::INDEX_TAG, _)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_cbor::{Deserializer, SequenceSerializer};
+    ///
+    /// let mut seq = SequenceSerializer::new(Vec::new());
+    /// seq.serialize("first").unwrap();
+    /// seq.serialize("second").unwrap();
+    /// let bytes = seq.finish_with_index().unwrap();
+    ///
+    /// let mut items = Deserializer::from_slice(&bytes).into_iter::<String>();
+    /// let mut strings = Vec::new();
+    /// while !bytes[items.byte_offset()..].starts_with(b"\xda\x63\x62\x69\x78") {
+    ///     strings.push(items.next().unwrap().unwrap());
+    /// }
+    /// assert_eq!(strings, ["first", "second"]);
+    ///
+    /// let index: Vec<u64> = serde_cbor::from_slice(&bytes[items.byte_offset() + 5..]).unwrap();
+    /// assert_eq!(index, [0, 6]);
+    /// ```
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn finish_with_index(mut self) -> Result<W> {
+        self.ser.write_u64(6, Self::INDEX_TAG)?;
+        self.offsets.serialize(&mut self.ser)?;
+        Ok(self.into_inner())
+    }
+
+    /// Unwraps the writer and the byte offset at which each item starts.
+    ///
+    /// Nothing is written to the sequence, so it can be extended later on.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn into_parts(self) -> (W, Vec<u64>) {
+        (self.ser.into_inner().into_inner(), self.offsets)
+    }
+
+    /// Unwraps the writer from the sequence serializer.
+    pub fn into_inner(self) -> W {
+        self.ser.into_inner().into_inner()
+    }
+}
@@ -0,0 +1,185 @@
+//! Byte strings that share the buffer of the input.
+//!
+//! When deserializing from `Bytes` with [`from_bytes`](../de/fn.from_bytes.html), a
+//! [`SharedBytes`] is a reference-counted slice of the input instead of a copy. With other
+//! deserializers and data formats the contents are copied into a new buffer.
+
+use core::cell::RefCell;
+use core::fmt;
+use core::ops::Deref;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use bytes::Bytes;
+
+/// The name of the newtype struct that a `SharedBytes` is deserialized as. The deserializer only
+/// looks up the `Bytes` of a byte string for this name.
+pub(crate) const SHARED_BYTES_NAME: &str = "\0cbor_shared_bytes";
+
+// Serde can only pass primitive values and slices to a visitor, so the `Bytes` that back a
+// borrowed byte string are handed over out of band. They are only set for the duration of a
+// single `visit_borrowed_bytes` call on the visitor of a `SharedBytes` and matched by address.
+thread_local! {
+    static LENT: RefCell<Option<Bytes>> = const { RefCell::new(None) };
+}
+
+/// Makes `bytes` available to the `SharedBytes` visitor called by `f`.
+pub(crate) fn lend<T, F>(bytes: Bytes, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    struct Restore(Option<Bytes>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            LENT.with(|lent| *lent.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(LENT.with(|lent| lent.borrow_mut().replace(bytes)));
+    f()
+}
+
+/// Takes the lent `Bytes` if they are exactly `slice`.
+fn take(slice: &[u8]) -> Option<Bytes> {
+    LENT.with(|lent| {
+        let mut lent = lent.borrow_mut();
+        match *lent {
+            Some(ref bytes) if bytes.as_ptr() == slice.as_ptr() && bytes.len() == slice.len() => {
+                lent.take()
+            }
+            _ => None,
+        }
+    })
+}
+
+/// A byte string backed by `Bytes`.
+///
+/// Like `serde_bytes::ByteBuf` this is serialized as a CBOR byte string, but deserializing it
+/// from `Bytes` with [`from_bytes`](../de/fn.from_bytes.html) doesn't copy the contents.
+///
+/// # Examples
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use serde_cbor::shared::SharedBytes;
+///
+/// let input = Bytes::from(serde_cbor::to_vec(&SharedBytes::from(vec![1, 2, 3])).unwrap());
+/// let bytes: SharedBytes = serde_cbor::de::from_bytes(&input).unwrap();
+/// assert_eq!(&bytes[..], [1, 2, 3]);
+/// assert_eq!(bytes.as_ptr(), input[1..].as_ptr());
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedBytes(Bytes);
+
+impl SharedBytes {
+    /// Creates a byte string from `Bytes`.
+    pub fn new(bytes: Bytes) -> SharedBytes {
+        SharedBytes(bytes)
+    }
+
+    /// Returns the underlying `Bytes`.
+    pub fn into_inner(self) -> Bytes {
+        self.0
+    }
+}
+
+impl fmt::Debug for SharedBytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, fmt)
+    }
+}
+
+impl Deref for SharedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Bytes> for SharedBytes {
+    fn from(bytes: Bytes) -> SharedBytes {
+        SharedBytes(bytes)
+    }
+}
+
+impl From<Vec<u8>> for SharedBytes {
+    fn from(bytes: Vec<u8>) -> SharedBytes {
+        SharedBytes(Bytes::from(bytes))
+    }
+}
+
+impl From<SharedBytes> for Bytes {
+    fn from(bytes: SharedBytes) -> Bytes {
+        bytes.0
+    }
+}
+
+impl Serialize for SharedBytes {
+    fn serialize<S: Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
+        s.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedBytes {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> core::result::Result<Self, D::Error> {
+        struct SharedBytesVisitor;
+
+        impl<'de> de::Visitor<'de> for SharedBytesVisitor {
+            type Value = SharedBytes;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("a byte string")
+            }
+
+            fn visit_borrowed_bytes<E: de::Error>(
+                self,
+                v: &'de [u8],
+            ) -> core::result::Result<SharedBytes, E> {
+                match take(v) {
+                    Some(bytes) => Ok(SharedBytes(bytes)),
+                    None => self.visit_bytes(v),
+                }
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> core::result::Result<SharedBytes, E> {
+                Ok(SharedBytes(Bytes::copy_from_slice(v)))
+            }
+
+            fn visit_byte_buf<E: de::Error>(
+                self,
+                v: Vec<u8>,
+            ) -> core::result::Result<SharedBytes, E> {
+                Ok(SharedBytes(Bytes::from(v)))
+            }
+
+            fn visit_newtype_struct<D>(self, d: D) -> core::result::Result<SharedBytes, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                d.deserialize_byte_buf(self)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> core::result::Result<SharedBytes, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(SharedBytes(Bytes::from(bytes)))
+            }
+        }
+
+        d.deserialize_newtype_struct(SHARED_BYTES_NAME, SharedBytesVisitor)
+    }
+}
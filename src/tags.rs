@@ -2,17 +2,96 @@
 use core::fmt;
 use core::marker::PhantomData;
 use serde::de::{
-    Deserialize, Deserializer, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+    Deserialize, Deserializer, EnumAccess, Error, SeqAccess, Unexpected, VariantAccess, Visitor,
 };
-use serde::forward_to_deserialize_any;
 use serde::ser::{Serialize, Serializer};
 
-/// signals that a newtype is from a CBOR tag
+#[cfg(all(feature = "tags", feature = "std"))]
+mod datetime;
+#[cfg(all(feature = "tags", feature = "std"))]
+mod typed;
+#[cfg(all(feature = "tags", feature = "std"))]
+pub use self::datetime::{epoch, rfc3339, Date, DateTime, Timestamp};
+#[cfg(all(feature = "tags", feature = "std"))]
+pub use self::typed::{
+    base64, base64url, encoded_cbor, mime, regex, uri, uuid, Base64, Base64Url, EncodedCbor, Mime,
+    Regex, Tag, TaggedValue, Uri, Uuid,
+};
+
+/// The name that tagged values are serialized and deserialized with.
+///
+/// A tagged value is serialized as a newtype struct with this name around the digits of the tag,
+/// see [`CBOR_TAG_DIGIT_NAMES`]. Types that want to see tags request them by
+/// deserializing a newtype struct with this name. The deserializer passes a tagged value to them
+/// as an enum variant with this name as the identifier, whose content is a tuple of the tag and
+/// the value. Untagged values are passed as a newtype struct.
 pub(crate) const CBOR_NEWTYPE_NAME: &str = "\0cbor_tag";
 
+/// The names of the newtype structs that pass the hexadecimal digits of a tag.
+///
+/// The tag of a tagged value is written as `TAG_DIGITS` nested newtype structs around the value,
+/// starting with the most significant digit. Other data formats ignore the names of newtype
+/// structs, so they only see the value.
+pub(crate) const CBOR_TAG_DIGIT_NAMES: [&str; 16] = [
+    "\0cbor_tag_0",
+    "\0cbor_tag_1",
+    "\0cbor_tag_2",
+    "\0cbor_tag_3",
+    "\0cbor_tag_4",
+    "\0cbor_tag_5",
+    "\0cbor_tag_6",
+    "\0cbor_tag_7",
+    "\0cbor_tag_8",
+    "\0cbor_tag_9",
+    "\0cbor_tag_a",
+    "\0cbor_tag_b",
+    "\0cbor_tag_c",
+    "\0cbor_tag_d",
+    "\0cbor_tag_e",
+    "\0cbor_tag_f",
+];
+
+/// The number of hexadecimal digits that a tag is passed as.
+pub(crate) const TAG_DIGITS: u32 = 16;
+
+/// Returns the digit that a newtype struct with this name passes.
+pub(crate) fn tag_digit(name: &str) -> Option<u64> {
+    CBOR_TAG_DIGIT_NAMES
+        .iter()
+        .position(|digit| *digit == name)
+        .map(|digit| digit as u64)
+}
+
+/// The digits of a tag that are left to serialize, followed by the value.
+struct TagDigits<'a, T: ?Sized> {
+    tag: u64,
+    digits: u32,
+    value: &'a T,
+}
+
+impl<'a, T: ?Sized + Serialize> Serialize for TagDigits<'a, T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self.digits.checked_sub(1) {
+            Some(digits) => {
+                let digit = (self.tag >> (4 * digits)) & 0xf;
+                let rest = TagDigits {
+                    tag: self.tag,
+                    digits,
+                    value: self.value,
+                };
+                s.serialize_newtype_struct(CBOR_TAG_DIGIT_NAMES[digit as usize], &rest)
+            }
+            None => self.value.serialize(s),
+        }
+    }
+}
+
 /// A value that is optionally tagged with a cbor tag
 ///
 /// this only serves as an intermediate helper for tag serialization or deserialization
+///
+/// Tags are only written and read by `serde_cbor` if the `tags` feature is enabled, otherwise
+/// `Tagged` behaves like its value. Other data formats only see the value.
 pub struct Tagged<T> {
     /// cbor tag
     pub tag: Option<u64>,
@@ -29,192 +108,123 @@ impl<T> Tagged<T> {
 
 impl<T: Serialize> Serialize for Tagged<T> {
     fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-        set_tag(self.tag);
-        let r = s.serialize_newtype_struct(CBOR_NEWTYPE_NAME, &self.value);
-        set_tag(None);
-        r
-    }
-}
-
-fn untagged<T>(value: T) -> Tagged<T> {
-    Tagged::new(None, value)
-}
-
-macro_rules! delegate {
-    ($name: ident, $type: ty) => {
-        fn $name<E: serde::de::Error>(self, v: $type) -> Result<Self::Value, E> {
-            T::deserialize(v.into_deserializer()).map(untagged)
+        match self.tag {
+            Some(tag) => {
+                let digits = TagDigits {
+                    tag,
+                    digits: TAG_DIGITS,
+                    value: &self.value,
+                };
+                s.serialize_newtype_struct(CBOR_NEWTYPE_NAME, &digits)
+            }
+            None => self.value.serialize(s),
         }
-    };
+    }
 }
 
-struct EnumDeserializer<A>(A);
-
-impl<'de, A> Deserializer<'de> for EnumDeserializer<A>
+/// Reads the tag and the value from the variant that a tagged value is passed as.
+pub(crate) fn tagged_variant<'de, A, T>(variant: A) -> Result<(u64, T), A::Error>
 where
-    A: EnumAccess<'de>,
+    A: VariantAccess<'de>,
+    T: Deserialize<'de>,
 {
-    type Error = A::Error;
-
-    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        visitor.visit_enum(self.0)
-    }
+    struct TagContentVisitor<T>(PhantomData<T>);
 
-    forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
-    }
-}
-
-struct NoneDeserializer<E>(PhantomData<E>);
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for TagContentVisitor<T> {
+        type Value = (u64, T);
 
-impl<'de, E> Deserializer<'de> for NoneDeserializer<E>
-where
-    E: serde::de::Error,
-{
-    type Error = E;
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a tag and a value")
+        }
 
-    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        visitor.visit_none()
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(u64, T), A::Error> {
+            let tag = seq
+                .next_element()?
+                .ok_or_else(|| Error::invalid_length(0, &self))?;
+            let value = seq
+                .next_element()?
+                .ok_or_else(|| Error::invalid_length(1, &self))?;
+            Ok((tag, value))
+        }
     }
 
-    forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
-    }
+    variant.tuple_variant(2, TagContentVisitor(PhantomData))
 }
 
-struct BytesDeserializer<'a, E>(&'a [u8], PhantomData<E>);
+/// The identifier of the enum variant that a tagged value is passed as.
+pub(crate) struct TagIdentifier;
 
-impl<'de, 'a, E> Deserializer<'de> for BytesDeserializer<'a, E>
-where
-    E: serde::de::Error,
-{
-    type Error = E;
+impl<'de> Deserialize<'de> for TagIdentifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TagIdentifierVisitor;
 
-    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        visitor.visit_bytes(self.0)
-    }
+        impl<'de> Visitor<'de> for TagIdentifierVisitor {
+            type Value = TagIdentifier;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("a cbor tag")
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<TagIdentifier, E> {
+                if v == CBOR_NEWTYPE_NAME {
+                    Ok(TagIdentifier)
+                } else {
+                    Err(E::invalid_value(Unexpected::Str(v), &self))
+                }
+            }
+        }
 
-    forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+        deserializer.deserialize_identifier(TagIdentifierVisitor)
     }
 }
 
-/// A visitor that intercepts *just* visit_newtype_struct and passes through everything else.
-struct MaybeTaggedVisitor<T>(PhantomData<T>);
+struct TaggedVisitor<T>(PhantomData<T>);
 
-impl<'de, T: Deserialize<'de>> Visitor<'de> for MaybeTaggedVisitor<T> {
+impl<'de, T: Deserialize<'de>> Visitor<'de> for TaggedVisitor<T> {
     type Value = Tagged<T>;
 
     fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.write_str("a cbor tag newtype")
     }
 
-    delegate!(visit_bool, bool);
-
-    delegate!(visit_i8, i8);
-    delegate!(visit_i16, i16);
-    delegate!(visit_i32, i32);
-    delegate!(visit_i64, i64);
-
-    delegate!(visit_u8, u8);
-    delegate!(visit_u16, u16);
-    delegate!(visit_u32, u32);
-    delegate!(visit_u64, u64);
-
-    delegate!(visit_f32, f32);
-    delegate!(visit_f64, f64);
-
-    delegate!(visit_char, char);
-    delegate!(visit_str, &str);
-    delegate!(visit_borrowed_str, &'de str);
-
-    #[cfg(feature = "std")]
-    delegate!(visit_byte_buf, Vec<u8>);
-
-    #[cfg(feature = "std")]
-    delegate!(visit_string, String);
-
-    fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
-        T::deserialize(BytesDeserializer(value, PhantomData)).map(untagged)
-    }
-
-    fn visit_borrowed_bytes<E: serde::de::Error>(self, value: &'de [u8]) -> Result<Self::Value, E> {
-        T::deserialize(serde::de::value::BorrowedBytesDeserializer::new(value)).map(untagged)
-    }
-
-    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
-        T::deserialize(().into_deserializer()).map(untagged)
-    }
-
-    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
-        T::deserialize(NoneDeserializer(PhantomData)).map(untagged)
-    }
-
-    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-        T::deserialize(deserializer).map(untagged)
-    }
-
-    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
-        T::deserialize(serde::de::value::SeqAccessDeserializer::new(seq)).map(untagged)
-    }
-
-    fn visit_map<V: MapAccess<'de>>(self, map: V) -> Result<Self::Value, V::Error> {
-        T::deserialize(serde::de::value::MapAccessDeserializer::new(map)).map(untagged)
-    }
-
-    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
-        T::deserialize(EnumDeserializer(data)).map(untagged)
-    }
-
-    fn visit_newtype_struct<D: serde::Deserializer<'de>>(
+    fn visit_newtype_struct<D: Deserializer<'de>>(
         self,
         deserializer: D,
     ) -> Result<Self::Value, D::Error> {
-        let t = get_tag();
-        T::deserialize(deserializer).map(|v| Tagged::new(t, v))
+        T::deserialize(deserializer).map(|value| Tagged::new(None, value))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (TagIdentifier, variant) = data.variant()?;
+        let (tag, value) = tagged_variant(variant)?;
+        Ok(Tagged::new(Some(tag), value))
     }
 }
 
-impl<'de, T: serde::de::Deserialize<'de>> serde::de::Deserialize<'de> for Tagged<T> {
-    fn deserialize<D: serde::de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        deserializer.deserialize_any(MaybeTaggedVisitor::<T>(PhantomData))
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tagged<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(CBOR_NEWTYPE_NAME, TaggedVisitor(PhantomData))
     }
 }
 
 /// function to get the current cbor tag
 ///
-/// The only place where it makes sense to call this function is within visit_newtype_struct of a serde visitor.
-/// This is a low level API. In most cases it is preferable to use Tagged
+/// Tags are no longer passed to visitors out of band, so this always returns `None`. Use
+/// [`Tagged`](struct.Tagged.html) or `Value` to read tags instead.
+#[deprecated(note = "always returns `None`, read tags with `Tagged` or `Value` instead")]
 pub fn current_cbor_tag() -> Option<u64> {
-    get_tag()
-}
-
-#[cfg(feature = "tags")]
-pub(crate) fn set_tag(value: Option<u64>) {
-    CBOR_TAG.with(|f| *f.borrow_mut() = value);
-}
-
-#[cfg(feature = "tags")]
-pub(crate) fn get_tag() -> Option<u64> {
-    CBOR_TAG.with(|f| *f.borrow())
-}
-
-#[cfg(not(feature = "tags"))]
-pub(crate) fn set_tag(_value: Option<u64>) {}
-
-#[cfg(not(feature = "tags"))]
-pub(crate) fn get_tag() -> Option<u64> {
     None
 }
 
-#[cfg(feature = "tags")]
-use std::cell::RefCell;
-
-#[cfg(feature = "tags")]
-thread_local!(static CBOR_TAG: RefCell<Option<u64>> = RefCell::new(None));
+/// Returns the error for a value that doesn't have the expected tag.
+#[cfg(all(feature = "tags", feature = "std"))]
+pub(crate) fn unexpected_tag<E: Error>(found: Option<u64>, expected: u64) -> E {
+    let found = match found {
+        Some(tag) => format!("tag {}", tag),
+        None => "untagged value".to_owned(),
+    };
+    E::invalid_value(
+        Unexpected::Other(&found),
+        &format!("tag {}", expected).as_str(),
+    )
+}
@@ -0,0 +1,602 @@
+//! Dates and times (tags 0, 1, 100 and 1004).
+//!
+//! A `DateTime` is a point in time with nanosecond precision. It is serialized as epoch-based
+//! seconds with tag 1 and deserialized from either tag 1 or an RFC 3339 string with tag 0.
+//! `SystemTime` and, with the `chrono` and `time` features, `chrono::DateTime<Utc>` and
+//! `time::OffsetDateTime` can be used in the same way through the `epoch` and `rfc3339`
+//! modules.
+//!
+//! Epoch-based seconds with a fractional part are written as a 64-bit float, which keeps only
+//! about microsecond precision for present-day timestamps. Use the `rfc3339` module where
+//! nanoseconds must survive a round trip.
+//!
+//! A `Date` is a calendar day. It is serialized as days since 1970-01-01 with tag 100 and
+//! deserialized from either tag 100 or an RFC 3339 full-date string with tag 1004.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::{self, Deserialize, Deserializer, Unexpected};
+use serde::ser::{self, Serialize, Serializer};
+
+use crate::error::{Error, ErrorCode};
+use crate::tags::{unexpected_tag, Tagged};
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// A point in time as seconds and nanoseconds since 1970-01-01T00:00:00Z.
+///
+/// The `Serialize` implementation uses the `epoch` encoding. It is lossy: a `DateTime` with
+/// nanoseconds is written as a floating point number and may deserialize to a slightly
+/// different value. Serialize through `rfc3339` to keep the exact value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    seconds: i64,
+    nanoseconds: u32,
+}
+
+impl DateTime {
+    /// Creates a `DateTime` from seconds and nanoseconds since the Unix epoch.
+    ///
+    /// Nanoseconds of a second or more are carried into the seconds.
+    pub fn new(seconds: i64, nanoseconds: u32) -> DateTime {
+        DateTime {
+            seconds: seconds.saturating_add(i64::from(nanoseconds / NANOS_PER_SEC)),
+            nanoseconds: nanoseconds % NANOS_PER_SEC,
+        }
+    }
+
+    /// Returns the whole seconds since the Unix epoch.
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Returns the nanoseconds within the second, always less than one billion.
+    pub fn nanoseconds(&self) -> u32 {
+        self.nanoseconds
+    }
+
+    fn from_f64(v: f64) -> Option<DateTime> {
+        // The range of `i64` as `f64`, excluding the upper bound.
+        if !(-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0).contains(&v) {
+            return None;
+        }
+        let seconds = v.floor();
+        let nanoseconds = ((v - seconds) * f64::from(NANOS_PER_SEC)).round() as u32;
+        Some(DateTime::new(seconds as i64, nanoseconds))
+    }
+
+    fn to_f64(self) -> f64 {
+        self.seconds as f64 + f64::from(self.nanoseconds) / f64::from(NANOS_PER_SEC)
+    }
+}
+
+/// A calendar day as days since 1970-01-01.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    days: i64,
+}
+
+impl Date {
+    /// Creates a `Date` from days since 1970-01-01.
+    pub fn from_days(days: i64) -> Date {
+        Date { days }
+    }
+
+    /// Creates a `Date` from a year, month and day in the proleptic Gregorian calendar.
+    ///
+    /// Returns `None` if the month or day is out of range.
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Option<Date> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date {
+            days: days_from_civil(year, month, day),
+        })
+    }
+
+    /// Returns the days since 1970-01-01.
+    pub fn days(&self) -> i64 {
+        self.days
+    }
+
+    /// Returns the year, month and day in the proleptic Gregorian calendar.
+    pub fn to_ymd(&self) -> (i64, u32, u32) {
+        civil_from_days(self.days)
+    }
+}
+
+/// Types that can be converted to and from a `DateTime`.
+///
+/// Implementing this trait allows a type to be used with the `epoch` and `rfc3339` modules.
+pub trait Timestamp: Sized {
+    /// Converts the value to a `DateTime`, or returns `None` if it is out of range.
+    fn to_date_time(&self) -> Option<DateTime>;
+
+    /// Creates a value from a `DateTime`, or returns `None` if it is out of range.
+    fn from_date_time(date_time: DateTime) -> Option<Self>;
+}
+
+impl Timestamp for DateTime {
+    fn to_date_time(&self) -> Option<DateTime> {
+        Some(*self)
+    }
+
+    fn from_date_time(date_time: DateTime) -> Option<DateTime> {
+        Some(date_time)
+    }
+}
+
+impl Timestamp for SystemTime {
+    fn to_date_time(&self) -> Option<DateTime> {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(d) => Some(DateTime::new(
+                i64::try_from(d.as_secs()).ok()?,
+                d.subsec_nanos(),
+            )),
+            Err(e) => {
+                let d = e.duration();
+                let seconds = -i64::try_from(d.as_secs()).ok()?;
+                match d.subsec_nanos() {
+                    0 => Some(DateTime::new(seconds, 0)),
+                    n => Some(DateTime::new(seconds - 1, NANOS_PER_SEC - n)),
+                }
+            }
+        }
+    }
+
+    fn from_date_time(date_time: DateTime) -> Option<SystemTime> {
+        let nanos = Duration::new(0, date_time.nanoseconds);
+        if date_time.seconds >= 0 {
+            let seconds = Duration::from_secs(date_time.seconds as u64);
+            UNIX_EPOCH.checked_add(seconds)?.checked_add(nanos)
+        } else {
+            let seconds = Duration::from_secs(date_time.seconds.wrapping_neg() as u64);
+            UNIX_EPOCH.checked_sub(seconds)?.checked_add(nanos)
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn to_date_time(&self) -> Option<DateTime> {
+        Some(DateTime::new(
+            self.timestamp(),
+            self.timestamp_subsec_nanos(),
+        ))
+    }
+
+    fn from_date_time(date_time: DateTime) -> Option<Self> {
+        use chrono::TimeZone;
+        chrono::Utc
+            .timestamp_opt(date_time.seconds, date_time.nanoseconds)
+            .single()
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn to_date_time(&self) -> Option<DateTime> {
+        Some(DateTime::new(self.unix_timestamp(), self.nanosecond()))
+    }
+
+    fn from_date_time(date_time: DateTime) -> Option<Self> {
+        let nanos = i128::from(date_time.seconds) * i128::from(NANOS_PER_SEC)
+            + i128::from(date_time.nanoseconds);
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for Date {
+    fn from(date: chrono::NaiveDate) -> Date {
+        use chrono::Datelike;
+        // 1970-01-01 is day 719163 counted from 0001-01-01 as day 1.
+        Date::from_days(i64::from(date.num_days_from_ce()) - 719_163)
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::Date> for Date {
+    fn from(date: time::Date) -> Date {
+        // 1970-01-01 is the Julian day 2440588.
+        Date::from_days(i64::from(date.to_julian_day()) - 2_440_588)
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// The conversions between days and civil dates follow
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - if month <= 2 { 1 } else { 0 };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = (if days >= 0 { days } else { days - 146_096 }) / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+impl fmt::Display for Date {
+    /// Writes the date in the RFC 3339 full-date format `YYYY-MM-DD`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.to_ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Writes the point in time in the RFC 3339 format in UTC, like `2013-03-21T20:04:00Z`.
+    ///
+    /// Fractional seconds are written without trailing zeros.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = self.seconds.div_euclid(86400);
+        let secs = self.seconds.rem_euclid(86400);
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}",
+            Date::from_days(days),
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )?;
+        if self.nanoseconds != 0 {
+            let frac = format!("{:09}", self.nanoseconds);
+            write!(f, ".{}", frac.trim_end_matches('0'))?;
+        }
+        f.write_str("Z")
+    }
+}
+
+/// A cursor over an RFC 3339 string.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> Error {
+        Error::syntax(ErrorCode::InvalidDateTime, self.pos as u64)
+    }
+
+    fn number(&mut self, digits: usize, max: u32) -> Result<u32, Error> {
+        let mut value = 0;
+        for _ in 0..digits {
+            match self.bytes.get(self.pos) {
+                Some(c) if c.is_ascii_digit() => value = value * 10 + u32::from(c - b'0'),
+                _ => return Err(self.error()),
+            }
+            self.pos += 1;
+        }
+        if value > max {
+            self.pos -= digits;
+            return Err(self.error());
+        }
+        Ok(value)
+    }
+
+    fn expect(&mut self, chars: &[u8]) -> Result<u8, Error> {
+        match self.bytes.get(self.pos) {
+            Some(c) if chars.contains(c) => {
+                self.pos += 1;
+                Ok(*c)
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    fn date(&mut self) -> Result<Date, Error> {
+        let year = i64::from(self.number(4, 9999)?);
+        self.expect(b"-")?;
+        let month = self.number(2, 12)?;
+        self.expect(b"-")?;
+        let start = self.pos;
+        let day = self.number(2, 31)?;
+        Date::from_ymd(year, month, day).ok_or_else(|| {
+            self.pos = start;
+            self.error()
+        })
+    }
+
+    fn date_time(&mut self) -> Result<DateTime, Error> {
+        let date = self.date()?;
+        self.expect(b"Tt ")?;
+        let hour = self.number(2, 23)?;
+        self.expect(b":")?;
+        let minute = self.number(2, 59)?;
+        self.expect(b":")?;
+        // Leap seconds are accepted and count as the first second of the next minute.
+        let second = self.number(2, 60)?;
+        let mut nanoseconds = 0;
+        if self.expect(b".").is_ok() {
+            let start = self.pos;
+            let mut scale = NANOS_PER_SEC;
+            while let Some(c) = self.bytes.get(self.pos).filter(|c| c.is_ascii_digit()) {
+                if scale == 1 {
+                    return Err(self.error());
+                }
+                scale /= 10;
+                nanoseconds += u32::from(c - b'0') * scale;
+                self.pos += 1;
+            }
+            if self.pos == start {
+                return Err(self.error());
+            }
+        }
+        let offset = match self.expect(b"Zz+-")? {
+            b'+' => self.offset()?,
+            b'-' => -self.offset()?,
+            _ => 0,
+        };
+        if self.pos != self.bytes.len() {
+            return Err(self.error());
+        }
+        let seconds =
+            date.days * 86400 + i64::from(hour * 3600 + minute * 60 + second) - i64::from(offset);
+        Ok(DateTime::new(seconds, nanoseconds))
+    }
+
+    fn offset(&mut self) -> Result<i32, Error> {
+        let hours = self.number(2, 23)?;
+        self.expect(b":")?;
+        let minutes = self.number(2, 59)?;
+        Ok((hours * 3600 + minutes * 60) as i32)
+    }
+}
+
+impl FromStr for Date {
+    type Err = Error;
+
+    /// Parses an RFC 3339 full-date like `2013-03-21`.
+    fn from_str(s: &str) -> Result<Date, Error> {
+        let mut parser = Parser {
+            bytes: s.as_bytes(),
+            pos: 0,
+        };
+        let date = parser.date()?;
+        if parser.pos != s.len() {
+            return Err(parser.error());
+        }
+        Ok(date)
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    /// Parses an RFC 3339 date-time like `2013-03-21T20:04:00Z` or
+    /// `2013-03-21T21:04:00.5+01:00`.
+    fn from_str(s: &str) -> Result<DateTime, Error> {
+        Parser {
+            bytes: s.as_bytes(),
+            pos: 0,
+        }
+        .date_time()
+    }
+}
+
+/// The content of a tag that represents a date or time.
+enum Content {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Content, D::Error> {
+        struct ContentVisitor;
+
+        impl<'de> de::Visitor<'de> for ContentVisitor {
+            type Value = Content;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("a number or string")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Content, E> {
+                i64::try_from(v)
+                    .map(Content::Integer)
+                    .map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Content, E> {
+                Ok(Content::Integer(v))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Content, E> {
+                Ok(Content::Float(v))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Content, E> {
+                Ok(Content::Text(v.to_owned()))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Content, E> {
+                Ok(Content::Text(v))
+            }
+        }
+
+        d.deserialize_any(ContentVisitor)
+    }
+}
+
+/// Deserializes the content of a tag and checks that it has one of the two given tags.
+///
+/// Untagged content is accepted, so that the types can also be read from formats without tags.
+fn deserialize_content<'de, D>(d: D, text_tag: u64, number_tag: u64) -> Result<Content, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tagged = Tagged::<Content>::deserialize(d)?;
+    let expected = match tagged.value {
+        Content::Text(_) => text_tag,
+        _ => number_tag,
+    };
+    match tagged.tag {
+        Some(tag) if tag != expected => Err(unexpected_tag(tagged.tag, expected)),
+        _ => Ok(tagged.value),
+    }
+}
+
+impl Serialize for DateTime {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        epoch::serialize(self, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<DateTime, D::Error> {
+        match deserialize_content(d, 0, 1)? {
+            Content::Integer(v) => Ok(DateTime::new(v, 0)),
+            Content::Float(v) => DateTime::from_f64(v)
+                .ok_or_else(|| de::Error::invalid_value(Unexpected::Float(v), &"a timestamp")),
+            Content::Text(v) => v.parse().map_err(|_| {
+                de::Error::invalid_value(Unexpected::Str(&v), &"an RFC 3339 date-time")
+            }),
+        }
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        Tagged::new(Some(100), self.days).serialize(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Date, D::Error> {
+        match deserialize_content(d, 1004, 100)? {
+            Content::Integer(v) => Ok(Date::from_days(v)),
+            Content::Float(v) => Err(de::Error::invalid_type(Unexpected::Float(v), &"a date")),
+            Content::Text(v) => v.parse().map_err(|_| {
+                de::Error::invalid_value(Unexpected::Str(&v), &"an RFC 3339 full-date")
+            }),
+        }
+    }
+}
+
+fn to_date_time<T: Timestamp, E: ser::Error>(value: &T) -> Result<DateTime, E> {
+    value
+        .to_date_time()
+        .ok_or_else(|| E::custom("timestamp out of range"))
+}
+
+fn from_date_time<'de, T: Timestamp, D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+    let date_time = DateTime::deserialize(d)?;
+    T::from_date_time(date_time).ok_or_else(|| de::Error::custom("timestamp out of range"))
+}
+
+/// Serializes a `Timestamp` as epoch-based seconds with tag 1.
+///
+/// Whole seconds are written as integers and other values as floating point numbers. Both
+/// forms and RFC 3339 strings with tag 0 are accepted during deserialization.
+///
+/// The floating point form is lossy. An `f64` has 53 bits of mantissa, so for present-day
+/// timestamps everything below about a microsecond is rounded away, and
+/// `DateTime::new(1363896240, 123456789)` comes back with 123456717 nanoseconds. Use the
+/// `rfc3339` module when nanoseconds must round-trip exactly.
+///
+/// ```rust
+/// use serde_derive::{Deserialize, Serialize};
+/// use std::time::SystemTime;
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Event {
+///     #[serde(with = "serde_cbor::tags::epoch")]
+///     created: SystemTime,
+/// }
+///
+/// // {"created": 1(1363896240)}
+/// let bytes = b"\xa1\x67created\xc1\x1a\x51\x4b\x67\xb0";
+/// let event: Event = serde_cbor::from_slice(bytes).unwrap();
+/// assert_eq!(serde_cbor::to_vec(&event).unwrap(), &bytes[..]);
+/// ```
+pub mod epoch {
+    use super::*;
+
+    /// Serializes the value with tag 1.
+    pub fn serialize<T, S>(value: &T, s: S) -> Result<S::Ok, S::Error>
+    where
+        T: Timestamp,
+        S: Serializer,
+    {
+        let date_time = to_date_time(value)?;
+        if date_time.nanoseconds == 0 {
+            Tagged::new(Some(1), date_time.seconds).serialize(s)
+        } else {
+            Tagged::new(Some(1), date_time.to_f64()).serialize(s)
+        }
+    }
+
+    /// Deserializes the value from tag 0 or 1.
+    pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+    where
+        T: Timestamp,
+        D: Deserializer<'de>,
+    {
+        from_date_time(d)
+    }
+}
+
+/// Serializes a `Timestamp` as an RFC 3339 string in UTC with tag 0.
+///
+/// Fractional seconds are written with up to nine digits, so every value round-trips
+/// exactly. Both RFC 3339 strings and epoch-based seconds with tag 1 are accepted during
+/// deserialization.
+pub mod rfc3339 {
+    use super::*;
+
+    /// Serializes the value with tag 0.
+    ///
+    /// Returns an error if the year is not between 0 and 9999.
+    pub fn serialize<T, S>(value: &T, s: S) -> Result<S::Ok, S::Error>
+    where
+        T: Timestamp,
+        S: Serializer,
+    {
+        let date_time = to_date_time(value)?;
+        // The seconds of 0000-01-01T00:00:00Z and 10000-01-01T00:00:00Z.
+        if !(-62_167_219_200..253_402_300_800).contains(&date_time.seconds) {
+            return Err(ser::Error::custom("year out of range for RFC 3339"));
+        }
+        Tagged::new(Some(0), date_time.to_string()).serialize(s)
+    }
+
+    /// Deserializes the value from tag 0 or 1.
+    pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+    where
+        T: Timestamp,
+        D: Deserializer<'de>,
+    {
+        from_date_time(d)
+    }
+}
@@ -0,0 +1,199 @@
+//! Values with a fixed tag.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use super::{unexpected_tag, Tagged};
+
+/// A tag number known at compile time.
+///
+/// Implement this trait for a marker type to use a tag with [`TaggedValue`]. Markers for some
+/// common tags from the [IANA registry](https://www.iana.org/assignments/cbor-tags) are provided.
+pub trait Tag {
+    /// The tag number.
+    const NUMBER: u64;
+}
+
+macro_rules! tag_markers {
+    ($($(#[$attr:meta])* $name:ident = $number:expr;)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            pub enum $name {}
+
+            impl Tag for $name {
+                const NUMBER: u64 = $number;
+            }
+        )*
+    };
+}
+
+tag_markers! {
+    /// Tag 24, a byte string containing an encoded CBOR data item.
+    EncodedCbor = 24;
+    /// Tag 32, a text string containing a URI.
+    Uri = 32;
+    /// Tag 33, a text string containing base64url encoded data.
+    Base64Url = 33;
+    /// Tag 34, a text string containing base64 encoded data.
+    Base64 = 34;
+    /// Tag 35, a text string containing a regular expression.
+    Regex = 35;
+    /// Tag 36, a text string containing a MIME message.
+    Mime = 36;
+    /// Tag 37, a byte string containing a binary UUID.
+    Uuid = 37;
+}
+
+/// A value that is always encoded with the tag `G`.
+///
+/// Decoding fails if the value has a different tag or no tag at all. The tag number is given by
+/// a marker type implementing [`Tag`].
+///
+/// ```rust
+/// use serde_cbor::tags::{TaggedValue, Uri};
+///
+/// let uri = TaggedValue::<Uri, _>::new("http://www.example.com".to_string());
+/// let bytes = serde_cbor::to_vec(&uri).unwrap();
+/// assert_eq!(&bytes[..2], b"\xd8\x20");
+/// let decoded: TaggedValue<Uri, String> = serde_cbor::from_slice(&bytes).unwrap();
+/// assert_eq!(decoded, uri);
+///
+/// // A string without the URI tag is rejected.
+/// let bytes = serde_cbor::to_vec(&"http://www.example.com").unwrap();
+/// assert!(serde_cbor::from_slice::<TaggedValue<Uri, String>>(&bytes).is_err());
+/// ```
+pub struct TaggedValue<G, T> {
+    /// The tagged value.
+    pub value: T,
+    tag: PhantomData<G>,
+}
+
+impl<G: Tag, T> TaggedValue<G, T> {
+    /// Wraps a value to be encoded with the tag `G`.
+    pub fn new(value: T) -> TaggedValue<G, T> {
+        TaggedValue {
+            value,
+            tag: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+// The trait impls are written by hand because derives would require bounds on the marker type.
+
+impl<G, T: Clone> Clone for TaggedValue<G, T> {
+    fn clone(&self) -> Self {
+        TaggedValue {
+            value: self.value.clone(),
+            tag: PhantomData,
+        }
+    }
+}
+
+impl<G, T: Copy> Copy for TaggedValue<G, T> {}
+
+impl<G: Tag, T: fmt::Debug> fmt::Debug for TaggedValue<G, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaggedValue")
+            .field("tag", &G::NUMBER)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<G, T: PartialEq> PartialEq for TaggedValue<G, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<G, T: Eq> Eq for TaggedValue<G, T> {}
+
+impl<G: Tag, T: Serialize> Serialize for TaggedValue<G, T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        Tagged::new(Some(G::NUMBER), &self.value).serialize(s)
+    }
+}
+
+impl<'de, G: Tag, T: Deserialize<'de>> Deserialize<'de> for TaggedValue<G, T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let tagged = Tagged::<T>::deserialize(d)?;
+        match tagged.tag {
+            Some(tag) if tag == G::NUMBER => Ok(TaggedValue::new(tagged.value)),
+            tag => Err(unexpected_tag(tag, G::NUMBER)),
+        }
+    }
+}
+
+macro_rules! with_modules {
+    ($($(#[$attr:meta])* $name:ident: $tag:ident;)*) => {
+        $(
+            $(#[$attr])*
+            pub mod $name {
+                use serde::de::{Deserialize, Deserializer};
+                use serde::ser::{Serialize, Serializer};
+
+                use super::{Tag, Tagged, TaggedValue};
+
+                /// Serializes the value with the tag.
+                pub fn serialize<T, S>(value: &T, s: S) -> Result<S::Ok, S::Error>
+                where
+                    T: Serialize,
+                    S: Serializer,
+                {
+                    Tagged::new(Some(super::$tag::NUMBER), value).serialize(s)
+                }
+
+                /// Deserializes the value and checks the tag.
+                pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+                where
+                    T: Deserialize<'de>,
+                    D: Deserializer<'de>,
+                {
+                    TaggedValue::<super::$tag, T>::deserialize(d).map(TaggedValue::into_inner)
+                }
+            }
+        )*
+    };
+}
+
+with_modules! {
+    /// Serialize and deserialize an encoded CBOR data item with tag 24.
+    ///
+    /// Use with `#[serde(with = "serde_cbor::tags::encoded_cbor")]` on a field holding the
+    /// encoded bytes, for example a `serde_bytes::ByteBuf`.
+    encoded_cbor: EncodedCbor;
+    /// Serialize and deserialize a URI with tag 32.
+    ///
+    /// Use with `#[serde(with = "serde_cbor::tags::uri")]`.
+    uri: Uri;
+    /// Serialize and deserialize base64url encoded text with tag 33.
+    ///
+    /// Use with `#[serde(with = "serde_cbor::tags::base64url")]`.
+    base64url: Base64Url;
+    /// Serialize and deserialize base64 encoded text with tag 34.
+    ///
+    /// Use with `#[serde(with = "serde_cbor::tags::base64")]`.
+    base64: Base64;
+    /// Serialize and deserialize a regular expression with tag 35.
+    ///
+    /// Use with `#[serde(with = "serde_cbor::tags::regex")]`.
+    regex: Regex;
+    /// Serialize and deserialize a MIME message with tag 36.
+    ///
+    /// Use with `#[serde(with = "serde_cbor::tags::mime")]`.
+    mime: Mime;
+    /// Serialize and deserialize a binary UUID with tag 37.
+    ///
+    /// Use with `#[serde(with = "serde_cbor::tags::uuid")]` on a field that serializes as a
+    /// 16-byte byte string, like `uuid::Uuid`.
+    uuid: Uuid;
+}
@@ -0,0 +1,390 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, IntoDeserializer, Unexpected};
+use serde::ser::{self, Serialize};
+
+use crate::de::CBOR_VALUE_NAME;
+use crate::embedded::ByteBuf;
+use crate::error::{Error, ErrorCode};
+use crate::ser::{CBOR_BIGNUM_NAME, CBOR_NEGATIVE_BIGNUM_NAME};
+use crate::tags::{tagged_variant, TagIdentifier};
+
+/// An integer of arbitrary size, encoded as a bignum with tag 2 or 3.
+///
+/// Like in CBOR a `BigInt` is stored as a sign and an unsigned magnitude `n`. A non-negative
+/// `BigInt` has the value `n` and a negative one has the value `-1 - n`.
+///
+/// Values that fit into an `i128` or `u128` are serialized like those types, that is as plain
+/// integers if possible and otherwise as the shortest bignum.
+///
+/// ```rust
+/// use serde_cbor::value::BigInt;
+///
+/// // 2^128, one more than the largest u128.
+/// let bytes = b"\xc2\x51\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+/// let value: BigInt = serde_cbor::from_slice(bytes).unwrap();
+/// assert!(!value.is_negative());
+/// assert_eq!(value.magnitude().len(), 17);
+/// assert_eq!(value.to_u128(), None);
+/// assert_eq!(serde_cbor::to_vec(&value).unwrap(), &bytes[..]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u8>,
+}
+
+impl BigInt {
+    /// Creates a `BigInt` from its sign and the big-endian bytes of its magnitude.
+    ///
+    /// Leading zero bytes of the magnitude are removed.
+    pub fn new(negative: bool, mut magnitude: Vec<u8>) -> BigInt {
+        let zeros = magnitude.iter().take_while(|&&b| b == 0).count();
+        magnitude.drain(..zeros);
+        BigInt {
+            negative,
+            magnitude,
+        }
+    }
+
+    /// Returns true if the value is `-1 - n` for the magnitude `n`.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the big-endian bytes of the magnitude without leading zeros.
+    pub fn magnitude(&self) -> &[u8] {
+        &self.magnitude
+    }
+
+    fn magnitude_u128(&self) -> Option<u128> {
+        if self.magnitude.len() > 16 {
+            return None;
+        }
+        Some(
+            self.magnitude
+                .iter()
+                .fold(0, |acc, &b| acc << 8 | u128::from(b)),
+        )
+    }
+
+    /// Returns the value as an `i128` if it is in range.
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = i128::try_from(self.magnitude_u128()?).ok()?;
+        if self.negative {
+            Some(-1 - magnitude)
+        } else {
+            Some(magnitude)
+        }
+    }
+
+    /// Returns the value as an `u128` if it is in range.
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative {
+            return None;
+        }
+        self.magnitude_u128()
+    }
+
+    /// Creates a `BigInt` from its sign and the big-endian bytes of its absolute value.
+    pub(crate) fn from_abs(negative: bool, mut abs: Vec<u8>) -> BigInt {
+        if negative && abs.iter().any(|&b| b != 0) {
+            sub_one(&mut abs);
+            BigInt::new(true, abs)
+        } else {
+            BigInt::new(false, abs)
+        }
+    }
+
+    /// Returns the big-endian bytes of the absolute value without leading zeros.
+    pub(crate) fn abs(&self) -> Vec<u8> {
+        let mut abs = self.magnitude.clone();
+        if self.negative {
+            add_one(&mut abs);
+        }
+        abs
+    }
+}
+
+/// Adds one to a big-endian unsigned integer.
+fn add_one(bytes: &mut Vec<u8>) {
+    for byte in bytes.iter_mut().rev() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            return;
+        }
+    }
+    bytes.insert(0, 1);
+}
+
+/// Subtracts one from a non-zero big-endian unsigned integer.
+fn sub_one(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut().rev() {
+        let (diff, borrow) = byte.overflowing_sub(1);
+        *byte = diff;
+        if !borrow {
+            return;
+        }
+    }
+}
+
+/// Multiplies a big-endian unsigned integer by `factor` and adds `addend`.
+pub(crate) fn mul_add(bytes: &mut Vec<u8>, factor: u8, addend: u8) {
+    let mut carry = u16::from(addend);
+    for byte in bytes.iter_mut().rev() {
+        let v = u16::from(*byte) * u16::from(factor) + carry;
+        *byte = v as u8;
+        carry = v >> 8;
+    }
+    if carry != 0 {
+        bytes.insert(0, carry as u8);
+    }
+}
+
+/// Divides a big-endian unsigned integer by `divisor` in place and returns the remainder.
+fn div_rem(bytes: &mut Vec<u8>, divisor: u8) -> u8 {
+    let mut rem = 0u16;
+    for byte in bytes.iter_mut() {
+        let v = rem << 8 | u16::from(*byte);
+        *byte = (v / u16::from(divisor)) as u8;
+        rem = v % u16::from(divisor);
+    }
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    bytes.drain(..zeros);
+    rem as u8
+}
+
+/// Returns the decimal digits of a big-endian unsigned integer.
+pub(crate) fn to_decimal(mut bytes: Vec<u8>) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(b'0' + div_rem(&mut bytes, 10));
+        if bytes.is_empty() {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str(&to_decimal(self.abs()))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = Error;
+
+    /// Parses an integer in decimal notation with an optional sign.
+    fn from_str(s: &str) -> Result<BigInt, Error> {
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let start = (s.len() - digits.len()) as u64;
+        if digits.is_empty() {
+            return Err(Error::syntax(ErrorCode::InvalidNumber, start));
+        }
+        let mut abs = Vec::new();
+        for (i, c) in digits.bytes().enumerate() {
+            if !c.is_ascii_digit() {
+                return Err(Error::syntax(ErrorCode::InvalidNumber, start + i as u64));
+            }
+            mul_add(&mut abs, 10, c - b'0');
+        }
+        Ok(BigInt::from_abs(negative, abs))
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(v: u128) -> BigInt {
+        BigInt::new(false, v.to_be_bytes().to_vec())
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(v: i128) -> BigInt {
+        if v < 0 {
+            BigInt::new(true, ((-1 - v) as u128).to_be_bytes().to_vec())
+        } else {
+            BigInt::new(false, (v as u128).to_be_bytes().to_vec())
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($wide:ty, $($for_type:ty),*) => {
+        $(
+            impl From<$for_type> for BigInt {
+                fn from(v: $for_type) -> BigInt {
+                    BigInt::from(<$wide>::from(v))
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(i128, i8, i16, i32, i64);
+impl_from!(u128, u8, u16, u32, u64);
+
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for Bytes<'a> {
+    fn serialize<S: ser::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(self.0)
+    }
+}
+
+impl Serialize for BigInt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        if let Some(v) = self.to_i128() {
+            return serializer.serialize_i128(v);
+        }
+        if let Some(v) = self.to_u128() {
+            return serializer.serialize_u128(v);
+        }
+        let name = if self.negative {
+            CBOR_NEGATIVE_BIGNUM_NAME
+        } else {
+            CBOR_BIGNUM_NAME
+        };
+        serializer.serialize_newtype_struct(name, &Bytes(&self.magnitude))
+    }
+}
+
+impl<'de> de::Deserialize<'de> for BigInt {
+    fn deserialize<D>(deserializer: D) -> Result<BigInt, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct BigIntVisitor;
+
+        impl<'de> de::Visitor<'de> for BigIntVisitor {
+            type Value = BigInt;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("an integer or bignum")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<BigInt, E> {
+                Ok(v.into())
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<BigInt, E> {
+                Ok(v.into())
+            }
+
+            fn visit_u128<E: de::Error>(self, v: u128) -> Result<BigInt, E> {
+                Ok(v.into())
+            }
+
+            fn visit_i128<E: de::Error>(self, v: i128) -> Result<BigInt, E> {
+                Ok(v.into())
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<BigInt, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                match Special::from_enum(data)? {
+                    Special::BigInt(v) | Special::Tag(_, v) => Ok(v),
+                    Special::Simple(v) => Err(de::Error::invalid_type(
+                        Unexpected::Unsigned(v.into()),
+                        &self,
+                    )),
+                }
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<BigInt, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(CBOR_VALUE_NAME, BigIntVisitor)
+    }
+}
+
+/// A data item that the deserializer passes to the visitor of a `Value` as an enum variant.
+pub(crate) enum Special<V> {
+    Simple(u8),
+    BigInt(BigInt),
+    Tag(u64, V),
+}
+
+impl<'de, V: de::Deserialize<'de>> Special<V> {
+    /// Decodes a simple value from a unit variant or a bignum from a newtype variant containing
+    /// its magnitude. The identifier of a bignum is its tag number plus 256. Other tagged values
+    /// are decoded from the variant that `Tagged` uses.
+    pub(crate) fn from_enum<A>(data: A) -> Result<Special<V>, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        match data.variant()? {
+            (SpecialId::Number(id @ 0..=0xff), variant) => {
+                de::VariantAccess::unit_variant(variant)?;
+                Ok(Special::Simple(id as u8))
+            }
+            (SpecialId::Number(id @ 0x102..=0x103), variant) => {
+                let ByteBuf(magnitude) = de::VariantAccess::newtype_variant(variant)?;
+                Ok(Special::BigInt(BigInt::new(id == 0x103, magnitude)))
+            }
+            (SpecialId::Number(id), _) => Err(de::Error::invalid_value(
+                Unexpected::Unsigned(id.into()),
+                &"a simple value or bignum",
+            )),
+            (SpecialId::Tag(TagIdentifier), variant) => {
+                let (tag, value) = tagged_variant(variant)?;
+                Ok(Special::Tag(tag, value))
+            }
+        }
+    }
+}
+
+/// The identifier of a simple value or bignum, or of a tagged value.
+enum SpecialId {
+    Number(u16),
+    Tag(TagIdentifier),
+}
+
+impl<'de> de::Deserialize<'de> for SpecialId {
+    fn deserialize<D>(deserializer: D) -> Result<SpecialId, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct SpecialIdVisitor;
+
+        impl<'de> de::Visitor<'de> for SpecialIdVisitor {
+            type Value = SpecialId;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("a simple value, bignum or tag")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<SpecialId, E> {
+                u16::try_from(v)
+                    .map(SpecialId::Number)
+                    .map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<SpecialId, E> {
+                TagIdentifier::deserialize(v.into_deserializer()).map(SpecialId::Tag)
+            }
+        }
+
+        deserializer.deserialize_identifier(SpecialIdVisitor)
+    }
+}
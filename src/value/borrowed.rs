@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Unexpected};
+use serde::ser::{self, Serialize};
+
+use crate::de::CBOR_VALUE_NAME;
+use crate::ser::CBOR_SIMPLE_NAME;
+use crate::tags::Tagged;
+use crate::value::bigint::Special;
+use crate::value::{BigInt, Value};
+
+/// A CBOR value that borrows its strings and byte strings from the input.
+///
+/// Unlike `Value` deserializing a `ValueRef` does not copy any string data, which makes it
+/// cheap to inspect a few fields of a large message. Use `Deserializer::from_slice` or
+/// `from_slice` to borrow from a byte slice. Indefinite-length strings are split into chunks
+/// in the input and can only be borrowed when deserializing from a mutable slice with
+/// `Deserializer::from_mut_slice`.
+///
+/// Maps keep their entries in the order they appear in the input, including duplicate keys.
+///
+/// ```rust
+/// use serde_cbor::value::ValueRef;
+///
+/// // {"name": "cbor", "data": h'010203'}
+/// let slice = b"\xa2\x64name\x64cbor\x64data\x43\x01\x02\x03";
+/// let value: ValueRef = serde_cbor::from_slice(slice).unwrap();
+/// match value {
+///     ValueRef::Map(entries) => {
+///         assert_eq!(entries[0], (ValueRef::Text("name"), ValueRef::Text("cbor")));
+///         assert_eq!(entries[1].1, ValueRef::Bytes(&[1, 2, 3]));
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ValueRef<'a> {
+    /// Represents the absence of a value.
+    Null,
+    /// Represents the value undefined.
+    Undefined,
+    /// Represents a boolean value.
+    Bool(bool),
+    /// Integer CBOR numbers.
+    Integer(i128),
+    /// Represents an integer outside the range of `i128`, encoded as a bignum.
+    BigInt(BigInt),
+    /// Represents a floating point value.
+    Float(f64),
+    /// Represents a byte string borrowed from the input.
+    Bytes(&'a [u8]),
+    /// Represents an UTF-8 encoded string borrowed from the input.
+    Text(&'a str),
+    /// Represents an array of values.
+    Array(Vec<ValueRef<'a>>),
+    /// Represents a map as a list of key-value pairs in input order.
+    Map(Vec<(ValueRef<'a>, ValueRef<'a>)>),
+    /// Represents a tagged value
+    Tag(u64, Box<ValueRef<'a>>),
+    /// Represents a simple value other than false, true, null and undefined.
+    Simple(u8),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Copies the borrowed data into an owned `Value`.
+    ///
+    /// If a map contains a key more than once, the last entry is kept.
+    pub fn to_owned(&self) -> Value {
+        match *self {
+            ValueRef::Null => Value::Null,
+            ValueRef::Undefined => Value::Undefined,
+            ValueRef::Simple(v) => Value::Simple(v),
+            ValueRef::Bool(v) => Value::Bool(v),
+            ValueRef::Integer(v) => Value::Integer(v),
+            ValueRef::BigInt(ref v) => Value::BigInt(v.clone()),
+            ValueRef::Float(v) => Value::Float(v),
+            ValueRef::Bytes(v) => Value::Bytes(v.to_vec()),
+            ValueRef::Text(v) => Value::Text(v.to_owned()),
+            ValueRef::Array(ref v) => Value::Array(v.iter().map(ValueRef::to_owned).collect()),
+            ValueRef::Map(ref v) => Value::Map(
+                v.iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                    .collect::<BTreeMap<_, _>>(),
+            ),
+            ValueRef::Tag(tag, ref v) => Value::Tag(tag, Box::new(v.as_ref().to_owned())),
+        }
+    }
+}
+
+impl<'a> Serialize for ValueRef<'a> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match *self {
+            ValueRef::Integer(v) => serializer.serialize_i128(v),
+            ValueRef::BigInt(ref v) => v.serialize(serializer),
+            ValueRef::Bytes(v) => serializer.serialize_bytes(v),
+            ValueRef::Text(v) => serializer.serialize_str(v),
+            ValueRef::Array(ref v) => v.serialize(serializer),
+            ValueRef::Map(ref v) => serializer.collect_map(v.iter().map(|(k, v)| (k, v))),
+            ValueRef::Tag(tag, ref v) => Tagged::new(Some(tag), v).serialize(serializer),
+            ValueRef::Float(v) => serializer.serialize_f64(v),
+            ValueRef::Bool(v) => serializer.serialize_bool(v),
+            ValueRef::Null => serializer.serialize_unit(),
+            ValueRef::Undefined => serializer.serialize_newtype_struct(CBOR_SIMPLE_NAME, &23u8),
+            ValueRef::Simple(v) => serializer.serialize_newtype_struct(CBOR_SIMPLE_NAME, &v),
+        }
+    }
+}
+
+impl<'de: 'a, 'a> de::Deserialize<'de> for ValueRef<'a> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<ValueRef<'a>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct ValueRefVisitor<'a>(PhantomData<ValueRef<'a>>);
+
+        impl<'de: 'a, 'a> de::Visitor<'de> for ValueRefVisitor<'a> {
+            type Value = ValueRef<'a>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("any valid CBOR value with borrowed strings")
+            }
+
+            #[inline]
+            fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Text(value))
+            }
+
+            #[inline]
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Err(de::Error::invalid_type(Unexpected::Str(value), &self))
+            }
+
+            #[inline]
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Bytes(v))
+            }
+
+            #[inline]
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Err(de::Error::invalid_type(Unexpected::Bytes(v), &self))
+            }
+
+            #[inline]
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Integer(v.into()))
+            }
+
+            #[inline]
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Integer(v.into()))
+            }
+
+            #[inline]
+            fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Integer(v))
+            }
+
+            #[inline]
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match i128::try_from(v) {
+                    Ok(v) => Ok(ValueRef::Integer(v)),
+                    Err(_) => Ok(ValueRef::BigInt(v.into())),
+                }
+            }
+
+            #[inline]
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Bool(v))
+            }
+
+            #[inline]
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_unit()
+            }
+
+            #[inline]
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Null)
+            }
+
+            #[inline]
+            fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: de::SeqAccess<'de>,
+            {
+                let mut vec = Vec::new();
+
+                while let Some(elem) = visitor.next_element()? {
+                    vec.push(elem);
+                }
+
+                Ok(ValueRef::Array(vec))
+            }
+
+            #[inline]
+            fn visit_map<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: de::MapAccess<'de>,
+            {
+                let mut entries = Vec::new();
+
+                while let Some(entry) = visitor.next_entry()? {
+                    entries.push(entry);
+                }
+
+                Ok(ValueRef::Map(entries))
+            }
+
+            #[inline]
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ValueRef::Float(v))
+            }
+
+            // The deserializer passes simple values, bignums and tagged values as enum variants.
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                match Special::from_enum(data)? {
+                    Special::Simple(23) => Ok(ValueRef::Undefined),
+                    Special::Simple(v) => Ok(ValueRef::Simple(v)),
+                    Special::BigInt(v) => match v.to_i128() {
+                        Some(v) => Ok(ValueRef::Integer(v)),
+                        None => Ok(ValueRef::BigInt(v)),
+                    },
+                    Special::Tag(tag, v) => Ok(ValueRef::Tag(tag, Box::new(v))),
+                }
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(CBOR_VALUE_NAME, ValueRefVisitor(PhantomData))
+    }
+}
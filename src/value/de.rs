@@ -1,6 +1,9 @@
 use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::fmt;
 
+use crate::de::CBOR_VALUE_NAME;
+use crate::value::bigint::Special;
 use crate::value::Value;
 use serde::de;
 
@@ -74,6 +77,17 @@ impl<'de> de::Deserialize<'de> for Value {
                 Ok(Value::Integer(v))
             }
 
+            #[inline]
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match i128::try_from(v) {
+                    Ok(v) => Ok(Value::Integer(v)),
+                    Err(_) => Ok(Value::BigInt(v.into())),
+                }
+            }
+
             #[inline]
             fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
             where
@@ -134,20 +148,31 @@ impl<'de> de::Deserialize<'de> for Value {
                 Ok(Value::Float(v))
             }
 
+            // The deserializer passes simple values, bignums and tagged values as enum variants.
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                match Special::from_enum(data)? {
+                    Special::Simple(23) => Ok(Value::Undefined),
+                    Special::Simple(v) => Ok(Value::Simple(v)),
+                    Special::BigInt(v) => match v.to_i128() {
+                        Some(v) => Ok(Value::Integer(v)),
+                        None => Ok(Value::BigInt(v)),
+                    },
+                    Special::Tag(tag, v) => Ok(Value::Tag(tag, Box::new(v))),
+                }
+            }
+
             fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
             where
                 D: serde::Deserializer<'de>,
             {
-                let tag = crate::tags::get_tag();
-                let inner = deserializer.deserialize_any(self);
-                match tag {
-                    Some(tag) => inner.map(|v| Value::Tag(tag, Box::new(v))),
-                    None => inner,
-                }
+                deserializer.deserialize_any(self)
             }
         }
 
-        deserializer.deserialize_any(ValueVisitor)
+        deserializer.deserialize_newtype_struct(CBOR_VALUE_NAME, ValueVisitor)
     }
 }
 
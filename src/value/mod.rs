@@ -1,11 +1,18 @@
 //! CBOR values, keys and serialization routines.
 
+pub(crate) mod bigint;
+mod borrowed;
 mod de;
 mod ser;
 
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::collections::BTreeMap;
+use std::convert::TryFrom;
 
+#[doc(inline)]
+pub use self::bigint::BigInt;
+#[doc(inline)]
+pub use self::borrowed::ValueRef;
 #[doc(inline)]
 pub use self::de::from_value;
 #[doc(inline)]
@@ -21,17 +28,20 @@ pub use self::ser::to_value;
 /// [RFC 7049 bis]: https://tools.ietf.org/html/draft-ietf-cbor-7049bis-04#section-2
 #[derive(Clone, Debug)]
 pub enum Value {
-    /// Represents the absence of a value or the value undefined.
+    /// Represents the absence of a value.
     Null,
+    /// Represents the value undefined.
+    Undefined,
     /// Represents a boolean value.
     Bool(bool),
     /// Integer CBOR numbers.
     ///
-    /// The biggest value that can be represented is 2^64 - 1.
-    /// While the smallest value is -2^64.
-    /// Values outside this range can't be serialized
-    /// and will cause an error.
+    /// Values outside the range from -2^64 to 2^64 - 1 are serialized as bignums.
     Integer(i128),
+    /// Represents an integer outside the range of `i128`, encoded as a bignum.
+    ///
+    /// Bignums within the range of `i128` are deserialized as `Value::Integer`.
+    BigInt(BigInt),
     /// Represents a floating point value.
     Float(f64),
     /// Represents a byte string.
@@ -53,8 +63,12 @@ pub enum Value {
     Map(BTreeMap<Value, Value>),
     /// Represents a tagged value
     Tag(u64, Box<Value>),
+    /// Represents a simple value other than false, true, null and undefined.
+    ///
+    /// The simple values 24 to 31 are reserved and can't be serialized.
+    Simple(u8),
     // The hidden variant allows the enum to be extended
-    // with variants for tags and simple values.
+    // with new variants.
     #[doc(hidden)]
     __Hidden,
 }
@@ -86,13 +100,19 @@ impl Ord for Value {
             return self.major_type().cmp(&other.major_type());
         }
         match (self, other) {
-            (Integer(a), Integer(b)) => a.abs().cmp(&b.abs()),
+            (Integer(a), Integer(b)) if self.major_type() != 6 => a.abs().cmp(&b.abs()),
             (Bytes(a), Bytes(b)) if a.len() != b.len() => a.len().cmp(&b.len()),
             (Text(a), Text(b)) if a.len() != b.len() => a.len().cmp(&b.len()),
             (Array(a), Array(b)) if a.len() != b.len() => a.len().cmp(&b.len()),
             (Map(a), Map(b)) if a.len() != b.len() => a.len().cmp(&b.len()),
             (Bytes(a), Bytes(b)) => a.cmp(b),
             (Text(a), Text(b)) => a.cmp(b),
+            (a, b) if a.simple_number().is_some() || b.simple_number().is_some() => {
+                // Simple values are encoded as their number and sort before floats. This also
+                // covers the reserved simple values, which can't be serialized.
+                let key = |v: &Value| v.simple_number().map_or(256, u16::from);
+                key(a).cmp(&key(b))
+            }
             (a, b) => {
                 let a = crate::to_vec(a).expect("self is serializable");
                 let b = crate::to_vec(b).expect("other is serializable");
@@ -117,12 +137,11 @@ impl_from!(Value::Integer, i8);
 impl_from!(Value::Integer, i16);
 impl_from!(Value::Integer, i32);
 impl_from!(Value::Integer, i64);
-// i128 omitted because not all numbers fit in CBOR serialization
+impl_from!(Value::Integer, i128);
 impl_from!(Value::Integer, u8);
 impl_from!(Value::Integer, u16);
 impl_from!(Value::Integer, u32);
 impl_from!(Value::Integer, u64);
-// u128 omitted because not all numbers fit in CBOR serialization
 impl_from!(Value::Float, f32);
 impl_from!(Value::Float, f64);
 impl_from!(Value::Bytes, Vec<u8>);
@@ -131,19 +150,42 @@ impl_from!(Value::Text, String);
 impl_from!(Value::Array, Vec<Value>);
 impl_from!(Value::Map, BTreeMap<Value, Value>);
 
+impl From<u128> for Value {
+    fn from(v: u128) -> Value {
+        match i128::try_from(v) {
+            Ok(v) => Value::Integer(v),
+            Err(_) => Value::BigInt(v.into()),
+        }
+    }
+}
+
+impl From<BigInt> for Value {
+    fn from(v: BigInt) -> Value {
+        match v.to_i128() {
+            Some(v) => Value::Integer(v),
+            None => Value::BigInt(v),
+        }
+    }
+}
+
 impl Value {
     fn major_type(&self) -> u8 {
         use self::Value::*;
         match self {
             Null => 7,
+            Undefined => 7,
+            Simple(_) => 7,
             Bool(_) => 7,
             Integer(v) => {
-                if *v >= 0 {
+                if *v >= 1 << 64 || *v < -(1 << 64) {
+                    6
+                } else if *v >= 0 {
                     0
                 } else {
                     1
                 }
             }
+            BigInt(_) => 6,
             Tag(_, _) => 6,
             Float(_) => 7,
             Bytes(_) => 2,
@@ -153,4 +195,16 @@ impl Value {
             __Hidden => unreachable!(),
         }
     }
+
+    /// Returns the number of a simple value, including false, true, null and undefined.
+    fn simple_number(&self) -> Option<u8> {
+        match *self {
+            Value::Bool(false) => Some(20),
+            Value::Bool(true) => Some(21),
+            Value::Null => Some(22),
+            Value::Undefined => Some(23),
+            Value::Simple(v) => Some(v),
+            _ => None,
+        }
+    }
 }
@@ -11,8 +11,10 @@ use std::collections::BTreeMap;
 use crate::error::Error;
 use serde::{self, Serialize};
 
-use crate::tags::Tagged;
-use crate::value::Value;
+use crate::embedded::CBOR_EMBEDDED_NAME;
+use crate::ser::{CBOR_BIGNUM_NAME, CBOR_NEGATIVE_BIGNUM_NAME, CBOR_SIMPLE_NAME};
+use crate::tags::{tag_digit, Tagged, CBOR_NEWTYPE_NAME, TAG_DIGITS};
+use crate::value::{BigInt, Value};
 
 impl serde::Serialize for Value {
     #[inline]
@@ -22,6 +24,7 @@ impl serde::Serialize for Value {
     {
         match *self {
             Value::Integer(v) => serializer.serialize_i128(v),
+            Value::BigInt(ref v) => v.serialize(serializer),
             Value::Bytes(ref v) => serializer.serialize_bytes(&v),
             Value::Text(ref v) => serializer.serialize_str(&v),
             Value::Array(ref v) => v.serialize(serializer),
@@ -30,12 +33,18 @@ impl serde::Serialize for Value {
             Value::Float(v) => serializer.serialize_f64(v),
             Value::Bool(v) => serializer.serialize_bool(v),
             Value::Null => serializer.serialize_unit(),
+            Value::Undefined => serializer.serialize_newtype_struct(CBOR_SIMPLE_NAME, &23u8),
+            Value::Simple(v) => serializer.serialize_newtype_struct(CBOR_SIMPLE_NAME, &v),
             Value::__Hidden => unreachable!(),
         }
     }
 }
 
-struct Serializer;
+struct Serializer {
+    /// The digits of a tag read so far and their number, set while serializing the digits of a
+    /// tagged value.
+    tag: Option<(u64, u32)>,
+}
 
 impl serde::Serializer for Serializer {
     type Ok = Value;
@@ -98,6 +107,10 @@ impl serde::Serializer for Serializer {
         Ok(Value::Integer(value.into()))
     }
 
+    fn serialize_u128(self, value: u128) -> Result<Value, Error> {
+        Ok(Value::from(value))
+    }
+
     #[inline]
     fn serialize_f32(self, value: f32) -> Result<Value, Error> {
         self.serialize_f64(f64::from(value))
@@ -147,13 +160,38 @@ impl serde::Serializer for Serializer {
     #[inline]
     fn serialize_newtype_struct<T: ?Sized>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Value, Error>
     where
         T: Serialize,
     {
-        value.serialize(self)
+        if name == CBOR_NEWTYPE_NAME {
+            return value.serialize(Serializer { tag: Some((0, 0)) });
+        }
+        if let (Some((tag, digits)), Some(digit)) = (self.tag, tag_digit(name)) {
+            let tag = tag << 4 | digit;
+            if digits + 1 < TAG_DIGITS {
+                return value.serialize(Serializer {
+                    tag: Some((tag, digits + 1)),
+                });
+            }
+            let value = value.serialize(Serializer { tag: None })?;
+            if cfg!(feature = "tags") {
+                return Ok(Value::Tag(tag, Box::new(value)));
+            }
+            return Ok(value);
+        }
+        match value.serialize(Serializer { tag: None })? {
+            Value::Integer(23) if name == CBOR_SIMPLE_NAME => Ok(Value::Undefined),
+            Value::Integer(v) if name == CBOR_SIMPLE_NAME => Ok(Value::Simple(v as u8)),
+            Value::Bytes(v) if name == CBOR_BIGNUM_NAME => Ok(BigInt::new(false, v).into()),
+            Value::Bytes(v) if name == CBOR_NEGATIVE_BIGNUM_NAME => Ok(BigInt::new(true, v).into()),
+            Value::Bytes(v) if name == CBOR_EMBEDDED_NAME => {
+                Ok(Value::Tag(24, Box::new(Value::Bytes(v))))
+            }
+            value => Ok(value),
+        }
     }
 
     fn serialize_newtype_variant<T: ?Sized>(
@@ -439,5 +477,5 @@ pub fn to_value<T>(value: T) -> Result<Value, Error>
 where
     T: Serialize,
 {
-    value.serialize(Serializer)
+    value.serialize(Serializer { tag: None })
 }
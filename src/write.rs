@@ -1,5 +1,7 @@
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::cmp;
 #[cfg(not(feature = "std"))]
 use core::fmt;
 #[cfg(feature = "std")]
@@ -16,6 +18,9 @@ use crate::error;
 /// Any object implementing `std::io::Write`
 /// can be wrapped in an [`IoWrite`](../write/struct.IoWrite.html) that implements
 /// this trait for the underlying object.
+///
+/// To serialize into output types outside of this crate, implement the stable
+/// [`Sink`](trait.Sink.html) trait and wrap it in a [`SinkWrite`](struct.SinkWrite.html).
 pub trait Write: private::Sealed {
     /// The type of error returned when a write operation fails.
     #[doc(hidden)]
@@ -24,6 +29,15 @@ pub trait Write: private::Sealed {
     /// Attempts to write an entire buffer into this write.
     #[doc(hidden)]
     fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+
+    /// Attempts to write several buffers one after another into this write.
+    #[doc(hidden)]
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        for buf in bufs {
+            self.write_all(buf)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(feature = "unsealed_read_write")]
@@ -38,12 +52,26 @@ pub trait Write: private::Sealed {
 ///
 /// This trait is sealed by default, enabling the `unsealed_read_write` feature removes this bound
 /// to allow objects outside of this crate to implement this trait.
+///
+/// To serialize into output types outside of this crate, implement the stable
+/// [`Sink`](trait.Sink.html) trait and wrap it in a [`SinkWrite`](struct.SinkWrite.html).
 pub trait Write {
     /// The type of error returned when a write operation fails.
     type Error: Into<error::Error>;
 
     /// Attempts to write an entire buffer into this write.
     fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+
+    /// Attempts to write several buffers one after another into this write.
+    ///
+    /// The serializer uses this to write the head of a byte or text string together with its
+    /// contents. The default implementation calls `write_all` for each buffer.
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        for buf in bufs {
+            self.write_all(buf)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(not(feature = "unsealed_read_write"))]
@@ -60,6 +88,10 @@ where
     fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
         (*self).write_all(buf)
     }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        (*self).write_all_vectored(bufs)
+    }
 }
 
 #[cfg(not(feature = "unsealed_read_write"))]
@@ -87,6 +119,43 @@ impl<W: io::Write> Write for IoWrite<W> {
     fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
         self.0.write_all(buf)
     }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        const MAX_SLICES: usize = 4;
+
+        let mut bufs = bufs;
+        // The number of bytes of `bufs[0]` that have been written.
+        let mut start = 0;
+        loop {
+            while let Some((first, rest)) = bufs.split_first() {
+                if start < first.len() {
+                    break;
+                }
+                start -= first.len();
+                bufs = rest;
+            }
+            if bufs.is_empty() {
+                return Ok(());
+            }
+            let mut slices = [io::IoSlice::new(&[]); MAX_SLICES];
+            let count = cmp::min(bufs.len(), MAX_SLICES);
+            slices[0] = io::IoSlice::new(&bufs[0][start..]);
+            for (slice, buf) in slices[1..count].iter_mut().zip(&bufs[1..]) {
+                *slice = io::IoSlice::new(buf);
+            }
+            match self.0.write_vectored(&slices[..count]) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                Ok(n) => start += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
 }
 
 #[cfg(all(feature = "std", not(feature = "unsealed_read_write")))]
@@ -100,6 +169,14 @@ impl Write for Vec<u8> {
         self.extend_from_slice(buf);
         Ok(())
     }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        self.reserve(bufs.iter().map(|buf| buf.len()).sum());
+        for buf in bufs {
+            self.extend_from_slice(buf);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(all(
@@ -169,7 +246,317 @@ impl<'a> Write for SliceWrite<'a> {
         self.index = end;
         Ok(())
     }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        let len = bufs.iter().map(|buf| buf.len()).sum::<usize>();
+        if self.slice.len() - self.index < len {
+            return Err(error::Error::scratch_too_small(self.index as u64));
+        }
+        for buf in bufs {
+            let end = self.index + buf.len();
+            self.slice[self.index..end].copy_from_slice(buf);
+            self.index = end;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(not(feature = "unsealed_read_write"))]
 impl<'a> private::Sealed for SliceWrite<'a> {}
+
+/// A `Write` that counts the bytes written to the underlying writer.
+#[derive(Debug)]
+pub(crate) struct CountingWrite<W> {
+    writer: W,
+    count: u64,
+}
+
+impl<W: Write> CountingWrite<W> {
+    pub(crate) fn new(writer: W) -> CountingWrite<W> {
+        CountingWrite { writer, count: 0 }
+    }
+
+    pub(crate) fn bytes_written(&self) -> u64 {
+        self.count
+    }
+
+    pub(crate) fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for CountingWrite<W> {
+    type Error = W::Error;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.writer.write_all(buf)?;
+        self.count += buf.len() as u64;
+        Ok(())
+    }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        self.writer.write_all_vectored(bufs)?;
+        self.count += bufs.iter().map(|buf| buf.len() as u64).sum::<u64>();
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "unsealed_read_write"))]
+impl<W> private::Sealed for CountingWrite<W> where W: Write {}
+
+/// A sink for serialized CBOR that can be implemented outside of this crate.
+///
+/// Unlike `Write` this trait is stable and doesn't need the `unsealed_read_write` feature. Wrap
+/// a sink in a [`SinkWrite`](struct.SinkWrite.html) to serialize into it, or use
+/// [`to_sink`](../ser/fn.to_sink.html).
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_cbor::ser::Sink;
+///
+/// /// A sink that only counts the bytes written to it.
+/// struct Counter(usize);
+///
+/// impl Sink for Counter {
+///     type Error = serde_cbor::Error;
+///
+///     fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+///         self.0 += buf.len();
+///         Ok(())
+///     }
+/// }
+///
+/// let mut counter = Counter(0);
+/// serde_cbor::ser::to_sink(&mut counter, &"hello").unwrap();
+/// assert_eq!(counter.0, 6);
+/// ```
+pub trait Sink {
+    /// The type of error returned when a write operation fails.
+    ///
+    /// Sinks without a suitable error type can use `serde_cbor::Error` and create errors with
+    /// `serde::ser::Error::custom`.
+    type Error: Into<error::Error>;
+
+    /// Attempts to write an entire buffer into this sink.
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+
+    /// Attempts to write several buffers one after another into this sink.
+    ///
+    /// Sinks that support scatter/gather I/O can override this to avoid copying large byte
+    /// strings. The default implementation calls `write_all` for each buffer.
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        for buf in bufs {
+            self.write_all(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S> Sink for &mut S
+where
+    S: Sink + ?Sized,
+{
+    type Error = S::Error;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(buf)
+    }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        (**self).write_all_vectored(bufs)
+    }
+}
+
+/// A wrapper for types that implement [`Sink`](trait.Sink.html) to implement the local
+/// [`Write`](trait.Write.html) trait.
+#[derive(Debug)]
+pub struct SinkWrite<S>(S);
+
+impl<S: Sink> SinkWrite<S> {
+    /// Wraps a `Sink` to make it compatible with [`Write`](trait.Write.html).
+    pub fn new(sink: S) -> SinkWrite<S> {
+        SinkWrite(sink)
+    }
+
+    /// Returns a reference to the underlying sink.
+    pub fn get_ref(&self) -> &S {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the underlying sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.0
+    }
+
+    /// Unwraps the sink.
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S: Sink> Write for SinkWrite<S> {
+    type Error = S::Error;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(buf)
+    }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        self.0.write_all_vectored(bufs)
+    }
+}
+
+#[cfg(not(feature = "unsealed_read_write"))]
+impl<S> private::Sealed for SinkWrite<S> where S: Sink {}
+
+/// The default capacity of a `BufWrite`, 8 KiB.
+#[cfg(any(feature = "std", feature = "alloc"))]
+const DEFAULT_BUF_CAPACITY: usize = 8 * 1024;
+
+/// A [`Write`](trait.Write.html) that collects small writes in a buffer.
+///
+/// The serializer writes every head of a data item separately, which is slow for writers like an
+/// unbuffered socket wrapped in an `IoWrite`. A `BufWrite` passes data to the underlying writer
+/// only once its buffer is full. Buffers at least as large as the capacity, like the contents of
+/// a large byte string, are written together with the buffered data in a single vectored write
+/// instead of being copied.
+///
+/// Like `std::io::BufWriter` the buffer is written when the `BufWrite` is dropped, but errors
+/// are ignored then. Call [`flush`](#method.flush) or [`into_inner`](#method.into_inner) after
+/// serializing to handle them.
+///
+/// `Write` can't tell how much of the data reached the underlying writer before an error, so the
+/// buffer is discarded when a write fails and nothing is written on drop afterwards. The output
+/// is incomplete in that case and should not be used.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde::Serialize;
+/// use serde_cbor::ser::{BufWrite, IoWrite};
+/// use serde_cbor::Serializer;
+///
+/// let mut bytes = Vec::new();
+/// let mut writer = BufWrite::with_capacity(64, IoWrite::new(&mut bytes));
+/// vec![1, 2, 3].serialize(&mut Serializer::new(&mut writer)).unwrap();
+/// writer.into_inner().unwrap();
+/// assert_eq!(bytes, b"\x83\x01\x02\x03");
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Debug)]
+pub struct BufWrite<W: Write> {
+    // Only `None` after `into_inner` has taken the writer.
+    writer: Option<W>,
+    buf: Vec<u8>,
+    capacity: usize,
+    // Set when a write to the underlying writer failed, which skips the flush on drop.
+    failed: bool,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<W: Write> BufWrite<W> {
+    /// Creates a buffered writer with a capacity of 8 KiB.
+    pub fn new(writer: W) -> BufWrite<W> {
+        BufWrite::with_capacity(DEFAULT_BUF_CAPACITY, writer)
+    }
+
+    /// Creates a buffered writer with the given capacity in bytes.
+    pub fn with_capacity(capacity: usize, writer: W) -> BufWrite<W> {
+        BufWrite {
+            writer: Some(writer),
+            buf: Vec::with_capacity(capacity),
+            capacity,
+            failed: false,
+        }
+    }
+
+    /// Writes the buffered data to the underlying writer.
+    ///
+    /// The buffer is discarded if this fails, since part of it may have been written already.
+    pub fn flush(&mut self) -> Result<(), W::Error> {
+        if !self.buf.is_empty() {
+            let writer = self.writer.as_mut().expect("writer taken");
+            let result = writer.write_all(&self.buf);
+            self.buf.clear();
+            self.failed |= result.is_err();
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Returns the data that has been buffered but not written yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns the capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.as_ref().expect("writer taken")
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly may reorder the output relative to the buffered data.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.as_mut().expect("writer taken")
+    }
+
+    /// Writes the buffered data and unwraps the underlying writer.
+    pub fn into_inner(mut self) -> Result<W, W::Error> {
+        self.flush()?;
+        Ok(self.writer.take().expect("writer taken"))
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<W: Write> Drop for BufWrite<W> {
+    fn drop(&mut self) {
+        if self.failed {
+            return;
+        }
+        if let (Some(writer), false) = (self.writer.as_mut(), self.buf.is_empty()) {
+            // Dropping can't report errors, `flush` or `into_inner` should be used for that.
+            let _ = writer.write_all(&self.buf);
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<W: Write> Write for BufWrite<W> {
+    type Error = W::Error;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.write_all_vectored(&[buf])
+    }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        for buf in bufs {
+            if buf.len() >= self.capacity {
+                // Write large buffers directly instead of copying them.
+                let writer = self.writer.as_mut().expect("writer taken");
+                let result = writer.write_all_vectored(&[&self.buf, buf]);
+                self.buf.clear();
+                self.failed |= result.is_err();
+                result?;
+                continue;
+            }
+            if self.capacity - self.buf.len() < buf.len() {
+                self.flush()?;
+            }
+            self.buf.extend_from_slice(buf);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(
+    any(feature = "std", feature = "alloc"),
+    not(feature = "unsealed_read_write")
+))]
+impl<W> private::Sealed for BufWrite<W> where W: Write {}
@@ -0,0 +1,173 @@
+#[cfg(feature = "async-tokio")]
+mod tokio_tests {
+    use futures_executor::{block_on, block_on_stream};
+    use serde_cbor::async_io::tokio::{
+        from_async_reader, to_async_writer, AsyncStreamDeserializer,
+    };
+    use serde_derive::{Deserialize, Serialize};
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio::io::{AsyncRead, ReadBuf};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    /// A reader that returns one byte at a time and is pending before every byte.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        pending: bool,
+    }
+
+    impl<'a> AsyncRead for Trickle<'a> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            if let Some((&byte, rest)) = self.bytes.split_first() {
+                buf.put_slice(&[byte]);
+                self.bytes = rest;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn test_tokio_roundtrip() {
+        let point = Point { x: 1, y: -1000 };
+        let mut bytes = Vec::new();
+        block_on(to_async_writer(&mut bytes, &point)).unwrap();
+        assert_eq!(bytes, serde_cbor::to_vec(&point).unwrap());
+
+        let reader = Trickle {
+            bytes: &bytes,
+            pending: false,
+        };
+        let decoded: Point = block_on(from_async_reader(reader)).unwrap();
+        assert_eq!(decoded, point);
+
+        // trailing data and truncated input
+        bytes.push(0);
+        assert!(block_on(from_async_reader::<Point, _>(&bytes[..])).is_err());
+        let truncated = &bytes[..bytes.len() - 2];
+        let error = block_on(from_async_reader::<Point, _>(truncated)).unwrap_err();
+        assert!(error.is_eof());
+    }
+
+    #[test]
+    fn test_tokio_stream() {
+        // 1, "a", 2 followed by a truncated item
+        let bytes = b"\x01\x61a\x02\x82\x01";
+        let reader = Trickle {
+            bytes,
+            pending: false,
+        };
+        let results =
+            block_on_stream(AsyncStreamDeserializer::<_, u32>::new(reader)).collect::<Vec<_>>();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), &1);
+        assert!(results[1].as_ref().unwrap_err().is_data());
+        assert_eq!(results[2].as_ref().unwrap(), &2);
+        assert!(results[3].as_ref().unwrap_err().is_eof());
+    }
+}
+
+#[cfg(feature = "async-futures")]
+mod futures_tests {
+    use futures_executor::{block_on, block_on_stream};
+    use serde_cbor::async_io::futures::{
+        from_async_reader, to_async_writer, AsyncStreamDeserializer,
+    };
+    use serde_cbor::Value;
+
+    #[test]
+    fn test_futures_roundtrip() {
+        let value = vec![Value::Integer(1), Value::Text("a".to_string())];
+        let mut bytes = Vec::new();
+        block_on(to_async_writer(&mut bytes, &value)).unwrap();
+        assert_eq!(bytes, b"\x82\x01\x61a");
+        let decoded: Vec<Value> = block_on(from_async_reader(&bytes[..])).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_futures_stream() {
+        let bytes = b"\x01\x02\x03";
+        let mut stream = block_on_stream(AsyncStreamDeserializer::<_, u32>::new(&bytes[..]));
+        assert_eq!(stream.next().unwrap().unwrap(), 1);
+        assert_eq!(stream.next().unwrap().unwrap(), 2);
+        assert_eq!(stream.next().unwrap().unwrap(), 3);
+        assert!(stream.next().is_none());
+    }
+}
+
+#[cfg(feature = "tokio-codec")]
+mod codec_tests {
+    use bytes::BytesMut;
+    use futures_executor::block_on_stream;
+    use serde_cbor::async_io::codec::CborCodec;
+    use tokio_util::codec::{Decoder, Encoder, FramedRead};
+
+    #[test]
+    fn test_codec_framed() {
+        let mut codec = CborCodec::<String>::new();
+        let mut buf = BytesMut::new();
+        for s in &["a", "bc", "def"] {
+            codec.encode(s, &mut buf).unwrap();
+        }
+        buf.extend_from_slice(b"\x62x");
+
+        let framed = FramedRead::new(&buf[..], CborCodec::<String>::new());
+        let results = block_on_stream(framed).collect::<Vec<_>>();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), "a");
+        assert_eq!(results[2].as_ref().unwrap(), "def");
+        assert!(results[3].as_ref().unwrap_err().is_eof());
+
+        // an integer isn't a string, but decoding continues after it
+        let mut buf = BytesMut::from(&b"\x01\x61a"[..]);
+        assert!(codec.decode(&mut buf).unwrap_err().is_data());
+        assert_eq!(codec.decode_eof(&mut buf).unwrap().unwrap(), "a");
+        assert!(codec.decode_eof(&mut buf).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_codec_incremental() {
+        let mut codec = CborCodec::<Vec<String>>::new();
+        // ["ab", "c"] followed by 1
+        let bytes = b"\x82\x62ab\x61c\x01";
+        let mut buf = BytesMut::new();
+        for &byte in &bytes[..5] {
+            buf.extend_from_slice(&[byte]);
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        }
+        buf.extend_from_slice(&bytes[5..]);
+        let value = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(value, ["ab", "c"]);
+        assert_eq!(&buf[..], b"\x01");
+    }
+
+    #[test]
+    fn test_codec_max_frame_len() {
+        let mut codec = CborCodec::<String>::new().max_frame_len(8);
+        // the length of the string is checked before its content arrives
+        let mut buf = BytesMut::from(&b"\x78\x20"[..]);
+        assert!(codec.decode(&mut buf).unwrap_err().is_syntax());
+
+        let mut codec = CborCodec::<String>::new().max_frame_len(8);
+        let mut buf = BytesMut::new();
+        assert!(codec.encode("too long to fit", &mut buf).is_err());
+        assert!(buf.is_empty());
+        codec.encode("short", &mut buf).unwrap();
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "short");
+    }
+}
@@ -1,6 +1,10 @@
 #[cfg(feature = "std")]
 mod std_tests {
+    use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+    use serde_cbor::ser::to_vec_canonical;
     use serde_cbor::value::Value;
+    use serde_derive::Serialize;
+    use std::collections::{BTreeMap, HashMap};
 
     #[test]
     fn integer_canonical_sort_order() {
@@ -101,4 +105,107 @@ mod std_tests {
         sorted.sort();
         assert_eq!(expected, sorted);
     }
+
+    #[test]
+    fn test_canonical_hash_map() {
+        let mut map = HashMap::new();
+        for i in 0..100u32 {
+            map.insert(i.to_string(), i);
+        }
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_by_key(|&(k, _)| (k.len(), k.clone()));
+        let mut expected = vec![0xb8, 100];
+        for (k, v) in sorted {
+            expected.extend(serde_cbor::to_vec(k).unwrap());
+            expected.extend(serde_cbor::to_vec(v).unwrap());
+        }
+        assert_eq!(to_vec_canonical(&map).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_canonical_key_order() {
+        let mut map = BTreeMap::new();
+        map.insert(Value::Integer(1000), Value::Null);
+        map.insert(Value::Text("a".to_owned()), Value::Null);
+        let map = Value::Map(map);
+
+        // 1000 encodes as 0x1903e8, "a" as 0x6161.
+        assert_eq!(
+            to_vec_canonical(&map).unwrap(),
+            b"\xa2\x19\x03\xe8\xf6\x61a\xf6"
+        );
+
+        let mut vec = Vec::new();
+        map.serialize(&mut serde_cbor::Serializer::new(&mut vec).legacy_canonical())
+            .unwrap();
+        assert_eq!(vec, b"\xa2\x61a\xf6\x19\x03\xe8\xf6");
+    }
+
+    #[test]
+    fn test_canonical_struct() {
+        #[derive(Serialize)]
+        struct Struct {
+            bb: u8,
+            a: u8,
+            c: HashMap<u8, u8>,
+        }
+        let mut c = HashMap::new();
+        c.insert(2, 0);
+        c.insert(1, 0);
+        let value = Struct { bb: 0, a: 1, c };
+        assert_eq!(
+            to_vec_canonical(&value).unwrap(),
+            b"\xa3\x61a\x01\x61c\xa2\x01\x00\x02\x00\x62bb\x00"
+        );
+
+        let mut vec = Vec::new();
+        value
+            .serialize(
+                &mut serde_cbor::Serializer::new(&mut vec)
+                    .packed_format()
+                    .canonical(),
+            )
+            .unwrap();
+        assert_eq!(vec, b"\xa3\x00\x00\x01\x01\x02\xa2\x01\x00\x02\x00");
+    }
+
+    #[test]
+    fn test_canonical_no_indefinite_length() {
+        struct Unsized;
+
+        impl Serialize for Unsized {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut seq = serializer.serialize_seq(None)?;
+                seq.serialize_element(&1)?;
+                seq.serialize_element(&[2, 3])?;
+                seq.end()
+            }
+        }
+
+        assert_eq!(
+            serde_cbor::to_vec(&Unsized).unwrap(),
+            b"\x9f\x01\x82\x02\x03\xff"
+        );
+        assert_eq!(to_vec_canonical(&Unsized).unwrap(), b"\x82\x01\x82\x02\x03");
+    }
+
+    #[test]
+    fn test_canonical_duplicate_keys() {
+        struct Duplicates;
+
+        impl Serialize for Duplicates {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("a", &1)?;
+                map.serialize_entry("a", &2)?;
+                map.end()
+            }
+        }
+
+        assert_eq!(
+            serde_cbor::to_vec(&Duplicates).unwrap(),
+            b"\xa2\x61a\x01\x61a\x02"
+        );
+        assert!(to_vec_canonical(&Duplicates).is_err());
+    }
 }
@@ -0,0 +1,241 @@
+#[cfg(all(feature = "tags", feature = "std"))]
+mod tagtests {
+    use serde_cbor::tags::{Date, DateTime};
+    use serde_cbor::value::Value;
+    use serde_cbor::{from_slice, to_vec};
+    use serde_derive::{Deserialize, Serialize};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Event {
+        #[serde(with = "serde_cbor::tags::epoch")]
+        epoch: SystemTime,
+        #[serde(with = "serde_cbor::tags::rfc3339")]
+        rfc3339: SystemTime,
+    }
+
+    #[test]
+    fn test_rfc_examples() {
+        // 0("2013-03-21T20:04:00Z")
+        let text = b"\xc0\x74\x32\x30\x31\x33\x2d\x30\x33\x2d\x32\x31\x54\x32\x30\x3a\x30\x34\x3a\x30\x30\x5a";
+        // 1(1363896240)
+        let int = b"\xc1\x1a\x51\x4b\x67\xb0";
+        // 1(1363896240.5)
+        let float = b"\xc1\xfb\x41\xd4\x52\xd9\xec\x20\x00\x00";
+
+        let expected = DateTime::new(1_363_896_240, 0);
+        assert_eq!(from_slice::<DateTime>(text).unwrap(), expected);
+        assert_eq!(from_slice::<DateTime>(int).unwrap(), expected);
+        assert_eq!(to_vec(&expected).unwrap(), &int[..]);
+
+        let expected = DateTime::new(1_363_896_240, 500_000_000);
+        assert_eq!(from_slice::<DateTime>(float).unwrap(), expected);
+        assert_eq!(to_vec(&expected).unwrap(), &float[..]);
+    }
+
+    #[test]
+    fn test_system_time() {
+        let time = UNIX_EPOCH + Duration::new(1_363_896_240, 250_000_000);
+        let event = Event {
+            epoch: time,
+            rfc3339: time,
+        };
+        let bytes = to_vec(&event).unwrap();
+        let value: Value = from_slice(&bytes).unwrap();
+        let text = Value::Text("2013-03-21T20:04:00.25Z".to_owned());
+        assert_eq!(
+            value,
+            Value::Map(
+                vec![
+                    (
+                        Value::Text("epoch".to_owned()),
+                        Value::Tag(1, Box::new(Value::Float(1_363_896_240.25)))
+                    ),
+                    (
+                        Value::Text("rfc3339".to_owned()),
+                        Value::Tag(0, Box::new(text))
+                    ),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+        assert_eq!(from_slice::<Event>(&bytes).unwrap(), event);
+
+        // Times before the epoch.
+        let time = UNIX_EPOCH - Duration::new(1, 250_000_000);
+        let event = Event {
+            epoch: time,
+            rfc3339: time,
+        };
+        assert_eq!(
+            from_slice::<Event>(&to_vec(&event).unwrap()).unwrap(),
+            event
+        );
+    }
+
+    #[test]
+    fn test_nanosecond_precision() {
+        let time = UNIX_EPOCH + Duration::new(1_363_896_240, 123_456_789);
+        let event = Event {
+            epoch: time,
+            rfc3339: time,
+        };
+        let decoded: Event = from_slice(&to_vec(&event).unwrap()).unwrap();
+        // The float encoding rounds away everything below a microsecond.
+        assert_ne!(decoded.epoch, time);
+        let error = decoded
+            .epoch
+            .duration_since(time)
+            .unwrap_or_else(|e| e.duration());
+        assert!(error < Duration::from_micros(1));
+        assert_eq!(decoded.rfc3339, time);
+
+        let date_time = DateTime::new(1_363_896_240, 123_456_789);
+        let decoded: DateTime = from_slice(&to_vec(&date_time).unwrap()).unwrap();
+        assert_eq!(decoded, DateTime::new(1_363_896_240, 123_456_717));
+        let text = date_time.to_string();
+        assert_eq!(text, "2013-03-21T20:04:00.123456789Z");
+        assert_eq!(text.parse::<DateTime>().unwrap(), date_time);
+    }
+
+    #[test]
+    fn test_date_time_strings() {
+        let cases = [
+            ("1970-01-01T00:00:00Z", 0, 0),
+            ("1969-12-31T23:59:59.999999999Z", -1, 999_999_999),
+            ("2000-02-29t12:00:00.5z", 951_825_600, 500_000_000),
+            ("2013-03-21 21:04:00+01:00", 1_363_896_240, 0),
+            ("2013-03-21T19:34:00-00:30", 1_363_896_240, 0),
+            ("2016-12-31T23:59:60Z", 1_483_228_800, 0),
+            ("0000-01-01T00:00:00Z", -62_167_219_200, 0),
+            ("9999-12-31T23:59:59Z", 253_402_300_799, 0),
+        ];
+        for &(s, seconds, nanoseconds) in cases.iter() {
+            let time: DateTime = s.parse().unwrap();
+            assert_eq!(time, DateTime::new(seconds, nanoseconds), "for {}", s);
+        }
+        assert_eq!(
+            DateTime::new(-1, 100_000_000).to_string(),
+            "1969-12-31T23:59:59.1Z"
+        );
+
+        let invalid = [
+            "",
+            "2013-03-21",
+            "2013-03-21T20:04:00",
+            "2013-03-21T20:04Z",
+            "2013-02-29T20:04:00Z",
+            "2013-13-01T20:04:00Z",
+            "2013-03-21T24:00:00Z",
+            "2013-03-21T20:04:00.Z",
+            "2013-03-21T20:04:00.1234567891Z",
+            "2013-03-21T20:04:00+0100",
+            "2013-03-21T20:04:00Zx",
+        ];
+        for s in invalid.iter() {
+            assert!(s.parse::<DateTime>().is_err(), "for {}", s);
+        }
+        let err = "2013-02-30T00:00:00Z".parse::<DateTime>().unwrap_err();
+        assert!(err.to_string().starts_with("invalid date or time"));
+        assert_eq!(err.offset(), 8);
+    }
+
+    #[test]
+    fn test_rfc3339_out_of_range() {
+        #[derive(Serialize)]
+        struct Time(#[serde(with = "serde_cbor::tags::rfc3339")] DateTime);
+        assert!(to_vec(&Time(DateTime::new(253_402_300_800, 0))).is_err());
+        assert!(to_vec(&Time(DateTime::new(-62_167_219_201, 0))).is_err());
+    }
+
+    #[test]
+    fn test_wrong_tag() {
+        // 1("2013-03-21T20:04:00Z")
+        assert!(from_slice::<DateTime>(b"\xc1\x742013-03-21T20:04:00Z").is_err());
+        // 0(0)
+        assert!(from_slice::<DateTime>(b"\xc0\x00").is_err());
+        // 1(Infinity)
+        assert!(from_slice::<DateTime>(b"\xc1\xf9\x7c\x00").is_err());
+        // 1(h'')
+        assert!(from_slice::<DateTime>(b"\xc1\x40").is_err());
+        // Untagged values are accepted.
+        assert_eq!(
+            from_slice::<DateTime>(b"\x00").unwrap(),
+            DateTime::new(0, 0)
+        );
+    }
+
+    #[test]
+    fn test_date() {
+        // 100(-4) and 1004("1969-12-28")
+        let days = b"\xd8\x64\x23";
+        let text = b"\xd9\x03\xec\x6a1969-12-28";
+        let date = Date::from_ymd(1969, 12, 28).unwrap();
+        assert_eq!(date.days(), -4);
+        assert_eq!(from_slice::<Date>(days).unwrap(), date);
+        assert_eq!(from_slice::<Date>(text).unwrap(), date);
+        assert_eq!(to_vec(&date).unwrap(), &days[..]);
+        assert_eq!(date.to_string(), "1969-12-28");
+
+        assert_eq!(Date::from_days(11_016).to_ymd(), (2000, 2, 29));
+        assert_eq!(Date::from_ymd(2100, 2, 29), None);
+        assert!("2000-02-29".parse::<Date>().is_ok());
+        assert!("1900-02-29".parse::<Date>().is_err());
+        assert!(from_slice::<Date>(b"\xd8\x64\xf9\x3c\x00").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+
+        #[derive(Debug, PartialEq, Deserialize, Serialize)]
+        struct Time(#[serde(with = "serde_cbor::tags::rfc3339")] chrono::DateTime<Utc>);
+
+        let time = Time(Utc.timestamp_opt(1_363_896_240, 5).unwrap());
+        let bytes = to_vec(&time).unwrap();
+        assert_eq!(bytes, b"\xc0\x78\x1e2013-03-21T20:04:00.000000005Z");
+        assert_eq!(from_slice::<Time>(&bytes).unwrap(), time);
+
+        let date = NaiveDate::from_ymd_opt(2013, 3, 21).unwrap();
+        assert_eq!(Date::from(date), Date::from_ymd(2013, 3, 21).unwrap());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time() {
+        use time::OffsetDateTime;
+
+        #[derive(Debug, PartialEq, Deserialize, Serialize)]
+        struct Time(#[serde(with = "serde_cbor::tags::epoch")] OffsetDateTime);
+
+        let time = Time(OffsetDateTime::from_unix_timestamp(-1_363_896_240).unwrap());
+        let bytes = to_vec(&time).unwrap();
+        assert_eq!(bytes, b"\xc1\x3a\x51\x4b\x67\xaf");
+        assert_eq!(from_slice::<Time>(&bytes).unwrap(), time);
+
+        let date = time.0.date();
+        assert_eq!(Date::from(date), Date::from_ymd(1926, 10, 13).unwrap());
+    }
+
+    #[test]
+    fn test_date_time_json() {
+        // formats without tags read the untagged seconds and strings
+        let date_time = DateTime::new(1_363_896_240, 500_000_000);
+        let json = serde_json::to_string(&date_time).unwrap();
+        assert_eq!(json, "1363896240.5");
+        assert_eq!(serde_json::from_str::<DateTime>(&json).unwrap(), date_time);
+
+        let event = Event {
+            epoch: UNIX_EPOCH + Duration::from_secs(1_363_896_240),
+            rfc3339: UNIX_EPOCH + Duration::from_secs(1_363_896_240),
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            r#"{"epoch":1363896240,"rfc3339":"2013-03-21T20:04:00Z"}"#
+        );
+        assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+    }
+}
@@ -48,6 +48,8 @@ mod std_tests {
     use std::collections::BTreeMap;
 
     use serde::de as serde_de;
+    use serde::Deserialize;
+    use serde_cbor::de::DuplicateKeys;
     use serde_cbor::value::Value;
     use serde_cbor::{de, error, to_vec, Deserializer};
 
@@ -259,7 +261,7 @@ mod std_tests {
                 Value::Integer(-456787678),
                 Value::Bool(true),
                 Value::Null,
-                Value::Null,
+                Value::Undefined,
                 Value::Float(23456543.5),
                 Value::Float(::std::f64::INFINITY)
             ]
@@ -744,4 +746,420 @@ mod std_tests {
         let err = serde_cbor::from_slice::<serde_cbor::Value>(&input).expect_err("recursion limit");
         assert!(err.is_syntax());
     }
+
+    fn from_slice_strict<'a, T>(slice: &'a [u8]) -> error::Result<T>
+    where
+        T: serde_de::Deserialize<'a>,
+    {
+        let mut deserializer = Deserializer::from_slice(slice).strict();
+        let value = serde_de::Deserialize::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    fn assert_strict_error<T>(slice: &[u8], message: &str, offset: u64)
+    where
+        T: serde_de::DeserializeOwned + std::fmt::Debug,
+    {
+        // The lenient default mode accepts the input.
+        de::from_slice::<T>(slice).unwrap();
+        let err = from_slice_strict::<T>(slice).unwrap_err();
+        assert!(err.is_syntax());
+        assert!(err.to_string().starts_with(message));
+        assert_eq!(err.offset(), offset);
+    }
+
+    #[test]
+    fn test_strict_accepts_canonical() {
+        let mut map = BTreeMap::new();
+        map.insert(Value::Integer(1000), Value::Float(1.5));
+        map.insert(Value::Text("a".to_owned()), Value::Float(100000.0));
+        map.insert(Value::Integer(-1), Value::Float(1.1));
+        let value = Value::Array(vec![
+            Value::Map(map),
+            Value::Tag(32, Box::new(Value::Bytes(vec![0; 24]))),
+            Value::Integer(0xffff_ffff_ffff_ffff),
+        ]);
+        let bytes = serde_cbor::ser::to_vec_canonical(&value).unwrap();
+        assert_eq!(from_slice_strict::<Value>(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn test_strict_non_shortest_argument() {
+        let message = "argument not in shortest form";
+        assert_strict_error::<u8>(b"\x18\x05", message, 0);
+        assert_strict_error::<i16>(b"\x39\x00\xff", message, 0);
+        assert_strict_error::<u32>(b"\x1a\x00\x00\xff\xff", message, 0);
+        assert_strict_error::<u64>(b"\x1b\x00\x00\x00\x00\xff\xff\xff\xff", message, 0);
+        assert_strict_error::<String>(b"\x78\x01a", message, 0);
+        assert_strict_error::<Vec<u8>>(b"\x82\x01\x18\x02", message, 2);
+        assert_strict_error::<Value>(b"\xd8\x01\x00", message, 0);
+    }
+
+    #[test]
+    fn test_strict_non_shortest_float() {
+        let message = "float not in shortest form";
+        assert_strict_error::<f32>(b"\xfa\x3f\xc0\x00\x00", message, 0);
+        assert_strict_error::<f64>(b"\xfb\x3f\xf8\x00\x00\x00\x00\x00\x00", message, 0);
+        assert_strict_error::<f64>(b"\xfb\x40\xf8\x6a\x00\x00\x00\x00\x00", message, 0);
+        assert_strict_error::<f64>(b"\xfa\x7f\xc0\x00\x00", message, 0);
+        from_slice_strict::<f64>(b"\xfa\x47\xc3\x50\x00").unwrap();
+        from_slice_strict::<f64>(b"\xfb\x3f\xf1\x99\x99\x99\x99\x99\x9a").unwrap();
+    }
+
+    #[test]
+    fn test_strict_indefinite_length() {
+        let message = "indefinite-length item";
+        assert_strict_error::<Vec<u8>>(b"\x9f\x01\xff", message, 0);
+        assert_strict_error::<Value>(b"\x5f\x41\x00\xff", message, 0);
+        assert_strict_error::<String>(b"\x7f\x61a\xff", message, 0);
+        assert_strict_error::<Value>(b"\x81\xbf\xff", message, 1);
+    }
+
+    #[test]
+    fn test_strict_map_keys() {
+        assert_strict_error::<BTreeMap<String, u8>>(
+            b"\xa2\x61b\x00\x61a\x00",
+            "map keys not in canonical order",
+            4,
+        );
+        assert_strict_error::<Value>(
+            b"\xa2\x61a\x00\x19\x03\xe8\x00",
+            "map keys not in canonical order",
+            4,
+        );
+        assert_strict_error::<BTreeMap<String, u8>>(
+            b"\xa2\x61a\x00\x61a\x01",
+            "duplicate map key",
+            4,
+        );
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    fn from_slice_duplicate_keys<'a, T>(slice: &'a [u8], policy: DuplicateKeys) -> error::Result<T>
+    where
+        T: serde_de::Deserialize<'a>,
+    {
+        let mut deserializer = Deserializer::from_slice(slice).duplicate_keys(policy);
+        let value = serde_de::Deserialize::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    #[test]
+    fn test_duplicate_keys_error() {
+        // {"x": 1, "y": 2, "x": 3}
+        let slice = b"\xa3\x61x\x01\x61y\x02\x61x\x03";
+        let err = from_slice_duplicate_keys::<BTreeMap<String, u8>>(slice, DuplicateKeys::Error)
+            .unwrap_err();
+        assert!(err.is_syntax());
+        assert!(err.to_string().starts_with("duplicate map key"));
+        assert_eq!(err.offset(), 7);
+
+        let err = from_slice_duplicate_keys::<Value>(slice, DuplicateKeys::Error).unwrap_err();
+        assert_eq!(err.offset(), 7);
+        let err = from_slice_duplicate_keys::<Point>(slice, DuplicateKeys::Error).unwrap_err();
+        assert_eq!(err.offset(), 7);
+
+        // The same map with indefinite length.
+        let slice = b"\xbf\x61x\x01\x61y\x02\x61x\x03\xff";
+        let err = from_slice_duplicate_keys::<Value>(slice, DuplicateKeys::Error).unwrap_err();
+        assert_eq!(err.offset(), 7);
+
+        // Keys that differ only in their value are fine.
+        let value: BTreeMap<String, u8> =
+            from_slice_duplicate_keys(b"\xa2\x61x\x01\x61y\x01", DuplicateKeys::Error).unwrap();
+        assert_eq!(value.len(), 2);
+    }
+
+    #[test]
+    fn test_duplicate_keys_first_wins() {
+        // {"x": 1, "y": [2], "x": [3, 4]}
+        let slice = b"\xa3\x61x\x01\x61y\x81\x02\x61x\x82\x03\x04";
+        let value: BTreeMap<String, Value> =
+            from_slice_duplicate_keys(slice, DuplicateKeys::FirstWins).unwrap();
+        assert_eq!(value["x"], Value::Integer(1));
+        assert_eq!(value.len(), 2);
+
+        let value: Value = from_slice_duplicate_keys(slice, DuplicateKeys::FirstWins).unwrap();
+        let mut expected = BTreeMap::new();
+        expected.insert(Value::Text("x".to_owned()), Value::Integer(1));
+        expected.insert(
+            Value::Text("y".to_owned()),
+            Value::Array(vec![Value::Integer(2)]),
+        );
+        assert_eq!(value, Value::Map(expected));
+
+        // {"x": 1, "y": 2, "x": 3}, also decoded from a reader.
+        let slice = b"\xbf\x61x\x01\x61y\x02\x61x\x03\xff";
+        let value: Point = from_slice_duplicate_keys(slice, DuplicateKeys::FirstWins).unwrap();
+        assert_eq!(value, Point { x: 1, y: 2 });
+        let mut deserializer =
+            Deserializer::from_reader(&slice[..]).duplicate_keys(DuplicateKeys::FirstWins);
+        let value: Point = serde_de::Deserialize::deserialize(&mut deserializer).unwrap();
+        deserializer.end().unwrap();
+        assert_eq!(value, Point { x: 1, y: 2 });
+
+        // Keys borrowed from the input, {"a": 1, "b": 2, "a": 3}.
+        let slice = b"\xa3\x61a\x01\x61b\x02\x61a\x03";
+        let value: BTreeMap<&str, u8> =
+            from_slice_duplicate_keys(slice, DuplicateKeys::FirstWins).unwrap();
+        assert_eq!(value["a"], 1);
+        assert_eq!(value["b"], 2);
+        let value: serde_cbor::value::ValueRef =
+            from_slice_duplicate_keys(&slice[..], DuplicateKeys::FirstWins).unwrap();
+        assert_eq!(
+            value.to_owned(),
+            de::from_slice::<Value>(b"\xa2\x61a\x01\x61b\x02").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keys_last_wins() {
+        let slice = b"\xa3\x61x\x01\x61y\x02\x61x\x03";
+        let value: BTreeMap<&str, u8> =
+            from_slice_duplicate_keys(slice, DuplicateKeys::LastWins).unwrap();
+        assert_eq!(value["x"], 3);
+        let value: BTreeMap<&str, u8> = de::from_slice(slice).unwrap();
+        assert_eq!(value["x"], 3);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        // [[[0]]]
+        let slice = b"\x81\x81\x81\x00";
+        let mut deserializer = Deserializer::from_slice(slice).max_depth(4);
+        let value: Value = serde_de::Deserialize::deserialize(&mut deserializer).unwrap();
+        assert_eq!(
+            value,
+            Value::Array(vec![Value::Array(vec![Value::Array(vec![
+                Value::Integer(0)
+            ])])])
+        );
+
+        let mut deserializer = Deserializer::from_slice(slice).max_depth(3);
+        let err = Value::deserialize(&mut deserializer).unwrap_err();
+        assert!(err.is_syntax());
+        assert!(err.to_string().starts_with("recursion limit exceeded"));
+        assert_eq!(err.offset(), 3);
+    }
+
+    #[test]
+    fn test_max_length() {
+        // ["abc", h'0102']
+        let slice = b"\x82\x63abc\x42\x01\x02";
+        let mut deserializer = Deserializer::from_slice(slice).max_length(3);
+        assert!(Value::deserialize(&mut deserializer).is_ok());
+
+        let mut deserializer = Deserializer::from_slice(slice).max_length(2);
+        let err = Value::deserialize(&mut deserializer).unwrap_err();
+        assert!(err.is_syntax());
+        assert!(err.to_string().starts_with("string length limit exceeded"));
+        assert_eq!(err.offset(), 2);
+
+        // (_ "ab", "cd")
+        let slice = b"\x7f\x62ab\x62cd\xff";
+        let mut deserializer = Deserializer::from_slice(slice).max_length(3);
+        let err = Value::deserialize(&mut deserializer).unwrap_err();
+        assert_eq!(err.offset(), 5);
+        let mut deserializer = Deserializer::from_reader(&slice[..]).max_length(4);
+        let value: String = serde_de::Deserialize::deserialize(&mut deserializer).unwrap();
+        assert_eq!(value, "abcd");
+
+        // A byte string claiming a length of 2^32 - 1 is rejected before it is read.
+        let slice = b"\x5a\xff\xff\xff\xff";
+        let mut deserializer = Deserializer::from_reader(&slice[..]).max_length(1024);
+        let err = Value::deserialize(&mut deserializer).unwrap_err();
+        assert!(err.to_string().starts_with("string length limit exceeded"));
+    }
+
+    #[test]
+    fn test_max_elements() {
+        // {1: [2, 3]}
+        let slice = b"\xa1\x01\x82\x02\x03";
+        let mut deserializer = Deserializer::from_slice(slice).max_elements(2);
+        assert!(Value::deserialize(&mut deserializer).is_ok());
+
+        let mut deserializer = Deserializer::from_slice(slice).max_elements(1);
+        let err = Value::deserialize(&mut deserializer).unwrap_err();
+        assert!(err.is_syntax());
+        assert!(err
+            .to_string()
+            .starts_with("collection size limit exceeded"));
+        assert_eq!(err.offset(), 3);
+
+        // [_ 1, 2, 3]
+        let slice = b"\x9f\x01\x02\x03\xff";
+        let mut deserializer = Deserializer::from_slice(slice).max_elements(2);
+        let err = Vec::<u8>::deserialize(&mut deserializer).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("collection size limit exceeded"));
+        assert_eq!(err.offset(), 3);
+
+        // {_ 1: 2, 3: 4}
+        let slice = b"\xbf\x01\x02\x03\x04\xff";
+        let mut deserializer = Deserializer::from_slice(slice).max_elements(1);
+        let err = Value::deserialize(&mut deserializer).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("collection size limit exceeded"));
+    }
+
+    #[test]
+    fn test_bignum() {
+        // Indefinite-length byte strings and leading zeros are accepted.
+        let value: u128 = de::from_slice(b"\xc2\x5f\x41\x00\x42\x01\x02\xff").unwrap();
+        assert_eq!(value, 0x0102);
+        let value: i128 = de::from_slice(b"\xc3\x41\x01").unwrap();
+        assert_eq!(value, -2);
+
+        let err = de::from_slice::<u128>(b"\xc3\x41\x01").unwrap_err();
+        assert_eq!(err.classify(), error::Category::Data);
+        let err =
+            de::from_slice::<i128>(b"\xc2\x50\x80\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0").unwrap_err();
+        assert_eq!(err.classify(), error::Category::Data);
+        let err =
+            de::from_slice::<u128>(b"\xc2\x51\x01\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0").unwrap_err();
+        assert!(err.to_string().starts_with("number out of range"));
+        assert_eq!(err.offset(), 19);
+        assert!(de::from_slice::<u128>(b"\xc2\x01").is_err());
+    }
+
+    #[test]
+    fn test_max_allocation() {
+        // "abc" "def", read as a stream of two values
+        let slice = b"\x63abc\x63def";
+        let deserializer = Deserializer::from_reader(&slice[..]).max_allocation(5);
+        let values: Vec<_> = deserializer.into_iter::<String>().collect();
+        assert_eq!(values[0].as_ref().unwrap(), "abc");
+        let err = values[1].as_ref().unwrap_err();
+        assert!(err.is_syntax());
+        assert!(err.to_string().starts_with("allocation limit exceeded"));
+        assert_eq!(err.offset(), 5);
+
+        // (_ h'01', h'0203')
+        let slice = b"\x5f\x41\x01\x42\x02\x03\xff";
+        let mut deserializer = Deserializer::from_slice(slice).max_allocation(3);
+        assert!(Value::deserialize(&mut deserializer).is_ok());
+        let mut deserializer = Deserializer::from_slice(slice).max_allocation(2);
+        assert!(Value::deserialize(&mut deserializer).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid: &[&[u8]] = &[
+            b"\x00",
+            b"\x3b\xff\xff\xff\xff\xff\xff\xff\xff",
+            b"\x43\x01\x02\x03",
+            b"\x7f\x61a\x62bc\xff",
+            b"\x83\x01\x9f\x02\xff\xa1\x61a\xf9\x3c\x00",
+            b"\xbf\x61a\xc1\x1a\x51\x4b\x67\xb0\xff",
+            b"\xfb\x3f\xf1\x99\x99\x99\x99\x99\x9a",
+            b"\xf6",
+        ];
+        for bytes in valid {
+            assert_eq!(de::validate(bytes).unwrap(), bytes.len());
+            let mut with_trailing = bytes.to_vec();
+            with_trailing.push(0x00);
+            assert_eq!(de::validate(&with_trailing).unwrap(), bytes.len());
+            de::from_slice::<serde::de::IgnoredAny>(bytes).unwrap();
+        }
+
+        let invalid: &[&[u8]] = &[
+            b"",
+            b"\x1c",
+            b"\x43\x01\x02",
+            b"\x5f\x61a\xff",
+            b"\x62\xc3\x28",
+            b"\x82\x01",
+            b"\x9f\x01",
+            b"\xa1\x01",
+            b"\xf8\x10",
+            b"\xff",
+        ];
+        for bytes in invalid {
+            assert!(de::validate(bytes).is_err(), "{:?}", bytes);
+            assert!(de::from_slice::<serde::de::IgnoredAny>(bytes).is_err());
+        }
+
+        // Simple values are well-formed, even though only `Value` can deserialize them.
+        assert_eq!(de::validate(b"\x82\xe0\xf8\xff").unwrap(), 4);
+
+        let err = de::validate(b"\x82\x62a\xff\x01").unwrap_err();
+        assert!(err.to_string().starts_with("invalid UTF-8"));
+        assert_eq!(err.offset(), 3);
+    }
+
+    #[test]
+    fn test_skip_value_long_text() {
+        // A text string longer than the skip buffer, with a two-byte character across the
+        // buffer boundary.
+        let text = format!("a{}", "\u{e9}".repeat(300));
+        let bytes = serde_cbor::to_vec(&text).unwrap();
+        assert_eq!(de::validate(&bytes).unwrap(), bytes.len());
+
+        let mut invalid = bytes.clone();
+        let len = invalid.len();
+        invalid[len - 1] = b'x';
+        let err = de::validate(&invalid).unwrap_err();
+        assert_eq!(err.offset(), len as u64 - 2);
+    }
+
+    #[test]
+    fn test_skip_value_reader() {
+        // 1, [2, "three"], {"a": h'04'}
+        let bytes = b"\x01\x82\x02\x65three\xa1\x61a\x41\x04";
+        let mut deserializer = Deserializer::from_reader(&bytes[..]);
+        assert_eq!(deserializer.skip_value().unwrap(), 1);
+        assert_eq!(deserializer.skip_value().unwrap(), 9);
+        assert_eq!(deserializer.skip_value().unwrap(), 14);
+        deserializer.end().unwrap();
+    }
+
+    #[test]
+    fn test_skip_value_limits() {
+        let mut deserializer = Deserializer::from_slice(b"\x81\x81\x00").max_depth(2);
+        assert!(deserializer.skip_value().is_err());
+        let mut deserializer = Deserializer::from_slice(b"\x63abc").max_length(2);
+        assert!(deserializer.skip_value().is_err());
+        let mut deserializer = Deserializer::from_slice(b"\xa2\x01\x02\x03\x04").max_elements(1);
+        assert!(deserializer.skip_value().is_err());
+
+        let mut deserializer = Deserializer::from_slice(b"\x18\x17").strict();
+        assert!(deserializer.skip_value().is_err());
+        let mut deserializer = Deserializer::from_slice(b"\x9f\xff").strict();
+        assert!(deserializer.skip_value().is_err());
+        let mut deserializer = Deserializer::from_slice(b"\xfa\x3f\x80\x00\x00").strict();
+        assert!(deserializer.skip_value().is_err());
+    }
+
+    #[test]
+    fn test_self_describe() {
+        // 55799({"a": 1})
+        let bytes = b"\xd9\xd9\xf7\xa1\x61a\x01";
+        assert!(serde_cbor::is_cbor(bytes));
+        assert!(!serde_cbor::is_cbor(&bytes[3..]));
+        assert!(!serde_cbor::is_cbor(&bytes[..2]));
+
+        let expected: BTreeMap<String, u8> = vec![("a".to_owned(), 1)].into_iter().collect();
+        let map: BTreeMap<String, u8> = de::from_slice(bytes).unwrap();
+        assert_eq!(map, expected);
+
+        let mut de = Deserializer::from_slice(bytes).strip_self_describe();
+        let value = Value::deserialize(&mut de).unwrap();
+        de.end().unwrap();
+        assert_eq!(value, serde_cbor::value::to_value(&expected).unwrap());
+
+        // A sequence of self-described items.
+        let bytes = b"\xd9\xd9\xf7\x01\xd9\xd9\xf7\x02";
+        let de = Deserializer::from_slice(bytes).strip_self_describe();
+        let values: Vec<Value> = de.into_iter().collect::<Result<_, _>>().unwrap();
+        assert_eq!(values, [Value::Integer(1), Value::Integer(2)]);
+    }
 }
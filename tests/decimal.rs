@@ -0,0 +1,143 @@
+#[cfg(all(feature = "tags", feature = "std"))]
+mod tagtests {
+    use serde_cbor::decimal::{BigFloat, Decimal};
+    use serde_cbor::value::{BigInt, Value};
+    use serde_cbor::{from_slice, to_vec};
+
+    #[test]
+    fn test_decimal_roundtrip() {
+        // 4([-2, 27315])
+        let bytes = b"\xc4\x82\x21\x19\x6a\xb3";
+        let decimal: Decimal = from_slice(bytes).unwrap();
+        assert_eq!(decimal, Decimal::new(-2, 27315));
+        assert_eq!(to_vec(&decimal).unwrap(), &bytes[..]);
+
+        // 4([-1, -18446744073709551617])
+        let bytes = b"\xc4\x82\x20\xc3\x49\x01\x00\x00\x00\x00\x00\x00\x00\x00";
+        let decimal: Decimal = from_slice(bytes).unwrap();
+        assert_eq!(decimal.to_string(), "-1844674407370955161.7");
+        assert_eq!(to_vec(&decimal).unwrap(), &bytes[..]);
+    }
+
+    #[test]
+    fn test_bigfloat_roundtrip() {
+        // 5([-1, 3])
+        let bytes = b"\xc5\x82\x20\x03";
+        let bigfloat: BigFloat = from_slice(bytes).unwrap();
+        assert_eq!(bigfloat, BigFloat::new(-1, 3));
+        assert_eq!(bigfloat.to_string(), "0x3p-1");
+        assert_eq!(to_vec(&bigfloat).unwrap(), &bytes[..]);
+    }
+
+    #[test]
+    fn test_invalid_structure() {
+        let invalid: &[&[u8]] = &[
+            // 5([-1, 3])
+            b"\xc5\x82\x20\x03",
+            // 4([-1])
+            b"\xc4\x81\x20",
+            // 4([-1, 3, 0])
+            b"\xc4\x83\x20\x03\x00",
+            // 4([2(h'01'), 3])
+            b"\xc4\x82\xc2\x41\x01\x03",
+            // 4([-1, 1.5])
+            b"\xc4\x82\x20\xf9\x3e\x00",
+            // 4("1.5")
+            b"\xc4\x631.5",
+        ];
+        for bytes in invalid {
+            assert!(from_slice::<Decimal>(bytes).is_err(), "for {:?}", bytes);
+        }
+        // Untagged arrays are accepted.
+        assert_eq!(
+            from_slice::<Decimal>(b"\x82\x20\x03").unwrap(),
+            Decimal::new(-1, 3)
+        );
+    }
+
+    #[test]
+    fn test_decimal_strings() {
+        let cases = [
+            ("0", 0, "0"),
+            ("-0", 0, "0"),
+            ("1.50", -2, "150"),
+            ("-0.05", -2, "-5"),
+            ("0.0000001", -7, "1"),
+            ("15e3", 3, "15"),
+            ("+1.5E-20", -21, "15"),
+            (
+                "123456789012345678901234567890.1",
+                -1,
+                "1234567890123456789012345678901",
+            ),
+        ];
+        for &(s, exponent, mantissa) in cases.iter() {
+            let decimal: Decimal = s.parse().unwrap();
+            assert_eq!(decimal.exponent, exponent, "for {}", s);
+            assert_eq!(
+                decimal.mantissa,
+                mantissa.parse::<BigInt>().unwrap(),
+                "for {}",
+                s
+            );
+            let reparsed: Decimal = decimal.to_string().parse().unwrap();
+            assert_eq!(reparsed, decimal, "for {}", s);
+        }
+        assert_eq!(Decimal::new(-20, 15).to_string(), "15e-20");
+        assert_eq!(Decimal::new(-3, -15).to_string(), "-0.015");
+
+        for s in [
+            "",
+            "-",
+            ".",
+            "1.2.3",
+            "1e",
+            "e5",
+            "1x",
+            "1e99999999999999999999",
+        ]
+        .iter()
+        {
+            assert!(s.parse::<Decimal>().is_err(), "for {}", s);
+        }
+        let err = "12a".parse::<Decimal>().unwrap_err();
+        assert!(err.to_string().starts_with("invalid number"));
+        assert_eq!(err.offset(), 2);
+    }
+
+    #[test]
+    fn test_bigfloat_strings() {
+        let bigfloat: BigFloat = "-0x1.8p3".parse().unwrap();
+        assert_eq!(bigfloat, BigFloat::new(-1, -24));
+        assert_eq!(bigfloat.to_string(), "-0x18p-1");
+        assert_eq!("0X10".parse::<BigFloat>().unwrap(), BigFloat::new(0, 16));
+        for s in ["", "0x", "1p3", "0xp3", "0x1q"].iter() {
+            assert!(s.parse::<BigFloat>().is_err(), "for {}", s);
+        }
+    }
+
+    #[test]
+    fn test_decimal_value() {
+        let value: Value = from_slice(&to_vec(&Decimal::new(-1, 5)).unwrap()).unwrap();
+        assert_eq!(
+            value,
+            Value::Tag(
+                4,
+                Box::new(Value::Array(vec![Value::Integer(-1), Value::Integer(5)]))
+            )
+        );
+    }
+
+    #[test]
+    fn test_decimal_json() {
+        // formats without tags read the untagged exponent and mantissa
+        let decimal: Decimal = "273.15".parse().unwrap();
+        let json = serde_json::to_string(&decimal).unwrap();
+        assert_eq!(json, "[-2,27315]");
+        assert_eq!(serde_json::from_str::<Decimal>(&json).unwrap(), decimal);
+
+        let bigfloat = BigFloat::new(-1, 3);
+        let json = serde_json::to_string(&bigfloat).unwrap();
+        assert_eq!(serde_json::from_str::<BigFloat>(&json).unwrap(), bigfloat);
+    }
+}
@@ -0,0 +1,204 @@
+#[cfg(feature = "std")]
+mod std_tests {
+    use serde_cbor::diag;
+    use serde_cbor::error::Category;
+    use serde_cbor::value::Value;
+    use std::collections::BTreeMap;
+
+    fn decode_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn assert_diag(hex: &str, expected: &str) {
+        let bytes = decode_hex(hex);
+        let diag = diag::to_string(&bytes).unwrap();
+        assert_eq!(diag, expected, "for {}", hex);
+        assert_eq!(diag::parse(&diag).unwrap(), bytes, "for {}", diag);
+    }
+
+    fn assert_parse(diag: &str, hex: &str) {
+        assert_eq!(diag::parse(diag).unwrap(), decode_hex(hex), "for {}", diag);
+    }
+
+    // The examples from RFC 8949, Appendix A.
+    #[test]
+    fn test_rfc_examples() {
+        assert_diag("00", "0");
+        assert_diag("17", "23");
+        assert_diag("1818", "24");
+        assert_diag("1903e8", "1000");
+        assert_diag("1b000000e8d4a51000", "1000000000000");
+        assert_diag("1bffffffffffffffff", "18446744073709551615");
+        assert_diag("3bffffffffffffffff", "-18446744073709551616");
+        assert_diag("20", "-1");
+        assert_diag("3903e7", "-1000");
+        assert_diag("f90000", "0.0");
+        assert_diag("f98000", "-0.0");
+        assert_diag("f93c00", "1.0");
+        assert_diag("fb3ff199999999999a", "1.1");
+        assert_diag("f93e00", "1.5");
+        assert_diag("f97bff", "65504.0");
+        assert_diag("fa47c35000", "100000.0");
+        assert_diag("fa7f7fffff", "3.4028234663852886e38");
+        assert_diag("fb7e37e43c8800759c", "1e300");
+        assert_diag("f90001", "5.960464477539063e-8");
+        assert_diag("f9c400", "-4.0");
+        assert_diag("fbc010666666666666", "-4.1");
+        assert_diag("f97c00", "Infinity");
+        assert_diag("f97e00", "NaN");
+        assert_diag("f9fc00", "-Infinity");
+        assert_diag("fa7f800000", "Infinity_2");
+        assert_diag("fb7ff8000000000000", "NaN_3");
+        assert_diag("f4", "false");
+        assert_diag("f5", "true");
+        assert_diag("f6", "null");
+        assert_diag("f7", "undefined");
+        assert_diag("f0", "simple(16)");
+        assert_diag("f8ff", "simple(255)");
+        assert_diag(
+            "c074323031332d30332d32315432303a30343a30305a",
+            "0(\"2013-03-21T20:04:00Z\")",
+        );
+        assert_diag("c11a514b67b0", "1(1363896240)");
+        assert_diag("d74401020304", "23(h'01020304')");
+        assert_diag("d818456449455446", "24(h'6449455446')");
+        assert_diag("40", "h''");
+        assert_diag("60", "\"\"");
+        assert_diag("6449455446", "\"IETF\"");
+        assert_diag("62225c", "\"\\\"\\\\\"");
+        assert_diag("62c3bc", "\"\u{fc}\"");
+        assert_diag("80", "[]");
+        assert_diag("8301820203820405", "[1, [2, 3], [4, 5]]");
+        assert_diag("a0", "{}");
+        assert_diag("a201020304", "{1: 2, 3: 4}");
+        assert_diag("a26161016162820203", "{\"a\": 1, \"b\": [2, 3]}");
+        assert_diag("5f42010243030405ff", "(_ h'0102', h'030405')");
+        assert_diag("7f657374726561646d696e67ff", "(_ \"strea\", \"ming\")");
+        assert_diag("9fff", "[_ ]");
+        assert_diag("9f018202039f0405ffff", "[_ 1, [2, 3], [_ 4, 5]]");
+        assert_diag("bf61610161629f0203ffff", "{_ \"a\": 1, \"b\": [_ 2, 3]}");
+        assert_diag("5fff", "''_");
+        assert_diag("7fff", "\"\"_");
+    }
+
+    #[test]
+    fn test_encoding_indicators() {
+        assert_diag("1800", "0_0");
+        assert_diag("190001", "1_1");
+        assert_diag("1a00000001", "1_2");
+        assert_diag("1b0000000000000001", "1_3");
+        assert_diag("380a", "-11_0");
+        assert_diag("5800", "h''_0");
+        assert_diag("780161", "\"a\"_0");
+        assert_diag("980101", "[_0 1]");
+        assert_diag("b9000201020202", "{_1 1: 2, 2: 2}");
+        assert_diag("d8010f", "1_0(15)");
+        assert_diag("fa3fc00000", "1.5_2");
+        assert_diag("fb3ff8000000000000", "1.5_3");
+        assert_diag("fa3f8ccccd", "1.100000023841858");
+        assert_diag("5f5800ff", "(_ h''_0)");
+    }
+
+    #[test]
+    fn test_text_escapes() {
+        assert_diag("640a0d0901", "\"\\n\\r\\t\\u0001\"");
+    }
+
+    #[test]
+    fn test_invalid() {
+        let invalid = [
+            "", "1c", "1f", "18", "4301", "5f01ff", "5f61ff", "62c328", "8201", "9f01", "f818",
+            "ff", "0000",
+        ];
+        for hex in invalid.iter() {
+            assert!(diag::to_string(&decode_hex(hex)).is_err(), "for {}", hex);
+        }
+
+        let err = diag::to_string(&decode_hex("0001")).unwrap_err();
+        assert!(err.to_string().starts_with("trailing data"));
+        assert_eq!(err.offset(), 2);
+    }
+
+    #[test]
+    fn test_value() {
+        let mut map = BTreeMap::new();
+        map.insert(Value::Text("b".to_owned()), Value::Bytes(vec![0xff]));
+        map.insert(Value::Integer(-2), Value::Float(0.5));
+        let value = Value::Array(vec![Value::Null, Value::Map(map), Value::Integer(1 << 40)]);
+        let diag = diag::value_to_string(&value).unwrap();
+        assert_eq!(diag, "[null, {-2: 0.5, \"b\": h'ff'}, 1099511627776]");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_parse("  [ 1 ,2 / two /, 3 ]  ", "83010203");
+        assert_parse("-0", "00");
+        assert_parse("0xff", "18ff");
+        assert_parse("-18446744073709551616", "3bffffffffffffffff");
+        assert_parse("1e300", "fb7e37e43c8800759c");
+        assert_parse("100000.0", "fa47c35000");
+        assert_parse("1.5_3", "fb3ff8000000000000");
+        assert_parse("-Infinity_3", "fbfff0000000000000");
+        assert_parse("h'01 02\n03'", "43010203");
+        assert_parse("'a\\'b'", "43612762");
+        assert_parse("b64'AQID'", "43010203");
+        assert_parse("b64'AQI='", "420102");
+        assert_parse("b64'-_8'", "42fbff");
+        assert_parse("\"\\ud83d\\ude00\"", "64f09f9880");
+        assert_parse("(_ '', h'01')", "5f404101ff");
+        assert_parse("simple(0)", "e0");
+        assert_parse("{_ \"a\": [_ ], \"b\": {}}", "bf61619fff6162a0ff");
+        assert_parse("55799([])", "d9d9f780");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let invalid = [
+            "",
+            "[1,]",
+            "[1 2]",
+            "{1}",
+            "{1: 2",
+            "h'0'",
+            "h'0g'",
+            "b64'A'",
+            "\"abc",
+            "1 2",
+            "256_0",
+            "1.1_1",
+            "simple(24)",
+            "simple(256)",
+            "(_ 1)",
+            "(_ h'', \"\")",
+            "'a'_",
+            "18446744073709551616",
+            "-18446744073709551617",
+            "nothing",
+            "1(2",
+            "/ comment",
+            "\"\\ud800\"",
+            "\"\\x\"",
+        ];
+        for diag in invalid.iter() {
+            assert!(diag::parse(diag).is_err(), "for {}", diag);
+        }
+
+        let err = diag::parse("[1, 2, nope]").unwrap_err();
+        assert_eq!(err.classify(), Category::Syntax);
+        assert!(err.to_string().starts_with("invalid diagnostic notation"));
+        assert_eq!(err.offset(), 7);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let value = diag::parse_value("[null, {-2: 0.5, \"b\": h'ff'}, 1099511627776]").unwrap();
+        let mut map = BTreeMap::new();
+        map.insert(Value::Text("b".to_owned()), Value::Bytes(vec![0xff]));
+        map.insert(Value::Integer(-2), Value::Float(0.5));
+        let expected = Value::Array(vec![Value::Null, Value::Map(map), Value::Integer(1 << 40)]);
+        assert_eq!(value, expected);
+    }
+}
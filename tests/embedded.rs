@@ -0,0 +1,108 @@
+#[cfg(feature = "std")]
+mod std_tests {
+    use serde::Deserialize;
+    use serde_cbor::embedded::Embedded;
+    use serde_cbor::value::{to_value, Value};
+    use serde_cbor::{from_reader, from_slice, to_vec, Deserializer};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Signed {
+        payload: Embedded<Vec<u32>>,
+        signature: u32,
+    }
+
+    #[test]
+    fn test_embedded_roundtrip() {
+        // [24(h'820102'), 3]
+        let bytes = b"\x82\xd8\x18\x43\x82\x01\x02\x03";
+        let signed: (Embedded<Vec<u32>>, u32) = from_slice(bytes).unwrap();
+        assert_eq!(signed.0.value(), &vec![1, 2]);
+        assert_eq!(signed.0.as_bytes(), b"\x82\x01\x02");
+        let signed: (Embedded<Vec<u32>>, u32) = from_reader(&bytes[..]).unwrap();
+        assert_eq!(signed.0.value(), &vec![1, 2]);
+        assert_eq!(to_vec(&signed).unwrap(), &bytes[..]);
+    }
+
+    #[test]
+    fn test_embedded_preserves_bytes() {
+        // 24(h'9f18011802ff'), the payload [_ 1, 2] with non-shortest integers
+        let bytes = b"\xd8\x18\x46\x9f\x18\x01\x18\x02\xff";
+        let payload: Embedded<Vec<u32>> = from_slice(bytes).unwrap();
+        assert_eq!(payload.value(), &vec![1, 2]);
+        assert_eq!(payload.as_bytes(), b"\x9f\x18\x01\x18\x02\xff");
+        assert_eq!(to_vec(&payload).unwrap(), &bytes[..]);
+
+        let (value, encoded) = payload.into_parts();
+        assert_eq!(value, [1, 2]);
+        assert_eq!(
+            Embedded::<Vec<u32>>::from_vec(encoded).unwrap().value(),
+            &value
+        );
+    }
+
+    #[test]
+    fn test_embedded_options() {
+        // The embedded item is decoded in strict mode as well.
+        let bytes = b"\xd8\x18\x46\x9f\x18\x01\x18\x02\xff";
+        let mut de = Deserializer::from_slice(bytes).strict();
+        assert!(Embedded::<Vec<u32>>::deserialize(&mut de).is_err());
+
+        // 24(h'8181818100'), the payload [[[[0]]]] nested one level deeper
+        let bytes = b"\xd8\x18\x45\x81\x81\x81\x81\x00";
+        let mut de = Deserializer::from_slice(bytes).max_depth(6);
+        assert!(Embedded::<Value>::deserialize(&mut de).is_ok());
+        let mut de = Deserializer::from_slice(bytes).max_depth(5);
+        assert!(Embedded::<Value>::deserialize(&mut de).is_err());
+    }
+
+    #[test]
+    fn test_embedded_allocation() {
+        // [24(h'63616263'), 24(h'63616263')], the payloads are both "abc"
+        let bytes = b"\x82\xd8\x18\x44\x63\x61\x62\x63\xd8\x18\x44\x63\x61\x62\x63";
+        let mut de = Deserializer::from_slice(bytes).max_allocation(14);
+        assert!(<(Embedded<String>, Embedded<String>)>::deserialize(&mut de).is_ok());
+        let mut de = Deserializer::from_slice(bytes).max_allocation(13);
+        assert!(<(Embedded<String>, Embedded<String>)>::deserialize(&mut de).is_err());
+    }
+
+    #[test]
+    fn test_embedded_invalid() {
+        let invalid: &[&[u8]] = &[
+            // h'820102' without a tag
+            b"\x43\x82\x01\x02",
+            // 25(h'820102')
+            b"\xd8\x19\x43\x82\x01\x02",
+            // 24([1, 2])
+            b"\xd8\x18\x82\x01\x02",
+            // 24(h'82010203'), trailing data in the payload
+            b"\xd8\x18\x44\x82\x01\x02\x03",
+            // 24(h'8201'), truncated payload
+            b"\xd8\x18\x42\x82\x01",
+        ];
+        for bytes in invalid {
+            assert!(from_slice::<Embedded<Vec<u32>>>(bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn test_embedded_value() {
+        let signed = Signed {
+            payload: Embedded::new(vec![1, 2]).unwrap(),
+            signature: 3,
+        };
+        let value = to_value(&signed).unwrap();
+        let payload = Value::Tag(24, Box::new(Value::Bytes(vec![0x82, 0x01, 0x02])));
+        assert_eq!(
+            value,
+            Value::Map(
+                vec![
+                    (Value::Text("payload".to_string()), payload),
+                    (Value::Text("signature".to_string()), Value::Integer(3)),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(feature = "std")]
+mod std_tests {
+    use serde_cbor::framing::{FrameDecoder, FrameEncoder, Framing};
+    use serde_derive::{Deserialize, Serialize};
+    use std::io::{self, Read};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Message {
+        id: u32,
+        body: String,
+    }
+
+    /// A reader that returns one byte per call and `WouldBlock` before every byte.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        blocked: bool,
+    }
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.blocked = !self.blocked;
+            if self.blocked {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let n = self.bytes.len().min(buf.len()).min(1);
+            buf[..n].copy_from_slice(&self.bytes[..n]);
+            self.bytes = &self.bytes[n..];
+            Ok(n)
+        }
+    }
+
+    fn messages() -> Vec<Message> {
+        (0..3)
+            .map(|id| Message {
+                id,
+                body: "x".repeat(id as usize * 10),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_framing_roundtrip() {
+        for &framing in &[Framing::Sequence, Framing::LengthPrefixed] {
+            let mut encoder = FrameEncoder::new(Vec::new(), framing);
+            for message in messages() {
+                encoder.encode(&message).unwrap();
+            }
+            let bytes = encoder.into_inner();
+
+            let mut decoder = FrameDecoder::new(&bytes[..], framing);
+            for message in messages() {
+                assert_eq!(decoder.decode::<Message>().unwrap(), Some(message));
+            }
+            assert_eq!(decoder.decode::<Message>().unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_framing_partial_reads() {
+        for &framing in &[Framing::Sequence, Framing::LengthPrefixed] {
+            let mut encoder = FrameEncoder::new(Vec::new(), framing);
+            for message in messages() {
+                encoder.encode(&message).unwrap();
+            }
+            let bytes = encoder.into_inner();
+
+            let reader = Trickle {
+                bytes: &bytes,
+                blocked: false,
+            };
+            let mut decoder = FrameDecoder::new(reader, framing);
+            let mut decoded = Vec::new();
+            loop {
+                match decoder.decode::<Message>() {
+                    Ok(Some(message)) => decoded.push(message),
+                    Ok(None) => break,
+                    Err(e) => assert!(e.is_io()),
+                }
+            }
+            assert_eq!(decoded, messages());
+        }
+    }
+
+    #[test]
+    fn test_framing_errors() {
+        // a truncated message
+        let mut decoder = FrameDecoder::new(&b"\x00\x00\x00\x02\x61"[..], Framing::LengthPrefixed);
+        assert!(decoder.decode::<String>().unwrap_err().is_eof());
+        let mut decoder = FrameDecoder::new(&b"\x62a"[..], Framing::Sequence);
+        assert!(decoder.decode::<String>().unwrap_err().is_eof());
+
+        // a message that doesn't decode is skipped with length prefixes
+        let bytes = b"\x00\x00\x00\x01\x01\x00\x00\x00\x02\x61a";
+        let mut decoder = FrameDecoder::new(&bytes[..], Framing::LengthPrefixed);
+        assert!(decoder.decode::<String>().unwrap_err().is_data());
+        assert_eq!(decoder.decode::<String>().unwrap().unwrap(), "a");
+
+        // the length is checked before the message is read
+        let bytes = b"\x00\x10\x00\x00";
+        let mut decoder = FrameDecoder::new(&bytes[..], Framing::LengthPrefixed).max_frame_len(16);
+        assert!(decoder.decode::<String>().unwrap_err().is_syntax());
+        let bytes = b"\x78\x20";
+        let mut decoder = FrameDecoder::new(&bytes[..], Framing::Sequence).max_frame_len(1);
+        assert!(decoder.decode::<String>().unwrap_err().is_syntax());
+    }
+
+    #[test]
+    fn test_framing_configure() {
+        let mut encoder = FrameEncoder::new(Vec::new(), Framing::Sequence);
+        encoder.encode(&vec![0u8; 1 << 20]).unwrap();
+        encoder.encode(&"abc").unwrap();
+        let bytes = encoder.into_inner();
+
+        let mut decoder = FrameDecoder::new(&bytes[..], Framing::Sequence);
+        assert_eq!(decoder.decode::<Vec<u8>>().unwrap().unwrap().len(), 1 << 20);
+        assert_eq!(decoder.decode::<String>().unwrap().unwrap(), "abc");
+
+        // the options apply to every message, errors have offsets in the whole input
+        let mut decoder =
+            FrameDecoder::new(&bytes[..], Framing::Sequence).configure(|de| de.max_elements(16));
+        assert!(decoder.decode::<Vec<u8>>().is_err());
+        assert_eq!(decoder.decode::<String>().unwrap().unwrap(), "abc");
+        let mut decoder = FrameDecoder::new(&b"\x01\x1c"[..], Framing::Sequence);
+        assert_eq!(decoder.decode::<u8>().unwrap().unwrap(), 1);
+        assert_eq!(decoder.decode::<u8>().unwrap_err().offset(), 1);
+    }
+}
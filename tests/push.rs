@@ -0,0 +1,153 @@
+#[cfg(feature = "std")]
+mod std_tests {
+    use serde_cbor::push::{PushDecoder, Status};
+    use serde_cbor::value::Value;
+    use serde_cbor::{from_slice, to_vec};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Event {
+        kind: String,
+        data: Vec<u64>,
+    }
+
+    #[test]
+    fn test_push_bytewise() {
+        let events = vec![
+            Event {
+                kind: "open".to_string(),
+                data: vec![],
+            },
+            Event {
+                kind: "write".to_string(),
+                data: vec![1, 1000, 1 << 40],
+            },
+        ];
+        let mut bytes = Vec::new();
+        for event in &events {
+            bytes.extend(to_vec(event).unwrap());
+        }
+
+        let mut decoder = PushDecoder::new();
+        let mut decoded = Vec::new();
+        for &byte in &bytes {
+            decoder.feed(&[byte]);
+            match decoder.decode::<Event>().unwrap() {
+                Status::Item(event) => decoded.push(event),
+                Status::NeedMore(n) => assert!(n >= 1),
+            }
+        }
+        assert_eq!(decoded, events);
+        assert!(decoder.buffer().is_empty());
+        assert_eq!(decoder.byte_offset(), bytes.len() as u64);
+    }
+
+    #[test]
+    fn test_push_need_more() {
+        let mut decoder = PushDecoder::new();
+        assert_eq!(decoder.decode::<Value>().unwrap(), Status::NeedMore(1));
+        // a byte string of length 300
+        decoder.feed(b"\x59\x01");
+        assert_eq!(decoder.decode::<Value>().unwrap(), Status::NeedMore(1));
+        decoder.feed(b"\x2c");
+        assert_eq!(decoder.decode::<Value>().unwrap(), Status::NeedMore(300));
+        decoder.feed(&[0; 299]);
+        assert_eq!(decoder.decode::<Value>().unwrap(), Status::NeedMore(1));
+        decoder.feed(&[0]);
+        assert_eq!(
+            decoder.decode::<Value>().unwrap(),
+            Status::Item(Value::Bytes(vec![0; 300]))
+        );
+    }
+
+    #[test]
+    fn test_push_indefinite() {
+        // {_ "a": [_ 1, (_ h'01', h'02')], "b": 24(2)} followed by 3
+        let bytes = b"\xbf\x61a\x9f\x01\x5f\x41\x01\x41\x02\xff\xff\x61b\xd8\x18\x02\xff\x03";
+        let expected: Value = from_slice(&bytes[..18]).unwrap();
+        let mut decoder = PushDecoder::new();
+        for &byte in &bytes[..17] {
+            decoder.feed(&[byte]);
+            assert_eq!(decoder.decode::<Value>().unwrap(), Status::NeedMore(1));
+        }
+        decoder.feed(&bytes[17..]);
+        assert_eq!(decoder.decode::<Value>().unwrap(), Status::Item(expected));
+        assert_eq!(decoder.decode::<u8>().unwrap(), Status::Item(3));
+        assert_eq!(decoder.decode::<u8>().unwrap(), Status::NeedMore(1));
+    }
+
+    #[test]
+    fn test_push_errors() {
+        let invalid: &[&[u8]] = &[
+            // a break outside of an indefinite-length item
+            b"\xff",
+            b"\x81\xff",
+            // a map with a key but no value
+            b"\xbf\x01\xff",
+            // an integer chunk in an indefinite-length string
+            b"\x5f\x01\xff",
+            // a reserved additional information
+            b"\x1c",
+            b"\x3f",
+        ];
+        for bytes in invalid {
+            let mut decoder = PushDecoder::new();
+            decoder.feed(bytes);
+            assert!(decoder.decode::<Value>().unwrap_err().is_syntax());
+        }
+
+        // a well-formed item of the wrong type is skipped
+        let mut decoder = PushDecoder::new();
+        decoder.feed(b"\x61a\x01");
+        assert!(decoder.decode::<u8>().unwrap_err().is_data());
+        assert_eq!(decoder.decode::<u8>().unwrap(), Status::Item(1));
+    }
+
+    #[test]
+    fn test_push_depth() {
+        // the scanner accepts the same nesting depth as the deserializer
+        for depth in 125..130 {
+            let mut bytes = vec![0x81; depth];
+            bytes.push(0x00);
+            let mut decoder = PushDecoder::new();
+            decoder.feed(&bytes);
+            assert_eq!(
+                decoder.decode::<Value>().is_ok(),
+                from_slice::<Value>(&bytes).is_ok()
+            );
+        }
+
+        // deeply nested input is rejected without buffering it completely
+        let mut decoder = PushDecoder::new();
+        decoder.feed(&[0x81; 1 << 20]);
+        let err = decoder.decode::<Value>().unwrap_err();
+        assert!(err.is_syntax());
+        assert_eq!(err.offset(), 127);
+
+        let mut decoder = PushDecoder::new();
+        decoder.feed(&[0xc1; 1 << 20]);
+        assert!(decoder.decode::<Value>().unwrap_err().is_syntax());
+    }
+
+    #[test]
+    fn test_push_options() {
+        // "abcdef" is rejected once its head is known
+        let mut decoder = PushDecoder::new().max_frame_len(4);
+        decoder.feed(b"\x63abc\x66a");
+        assert_eq!(
+            decoder.decode::<String>().unwrap(),
+            Status::Item("abc".to_string())
+        );
+        let err = decoder.decode::<String>().unwrap_err();
+        assert!(err.is_syntax());
+        assert_eq!(err.offset(), 4);
+
+        let mut decoder = PushDecoder::new().configure(|de| de.max_length(2));
+        decoder.feed(b"\x63abc\x62ab");
+        assert!(decoder.decode::<String>().unwrap_err().is_syntax());
+        assert_eq!(
+            decoder.decode::<String>().unwrap(),
+            Status::Item("ab".to_string())
+        );
+    }
+}
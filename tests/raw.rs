@@ -0,0 +1,84 @@
+#[cfg(feature = "std")]
+mod std_tests {
+    use serde_cbor::raw::RawValue;
+    use serde_cbor::ser::to_vec_canonical;
+    use serde_cbor::value::Value;
+    use serde_cbor::{from_reader, from_slice, to_vec};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Envelope {
+        to: String,
+        body: RawValue,
+    }
+
+    #[test]
+    fn test_raw_value_roundtrip() {
+        // {"to": "bob", "body": {_ "b": (_ h'01', h'02'), "a": 1.0}}
+        let bytes = b"\xa2\x62to\x63bob\x64body\xbf\x61b\x5f\x41\x01\x41\x02\xff\x61a\xfb\x3f\xf0\x00\x00\x00\x00\x00\x00\xff";
+        let body = &bytes[13..];
+
+        let envelope: Envelope = from_slice(bytes).unwrap();
+        assert_eq!(envelope.to, "bob");
+        assert_eq!(envelope.body.as_bytes(), body);
+        let envelope: Envelope = from_reader(&bytes[..]).unwrap();
+        assert_eq!(envelope.body.as_bytes(), body);
+
+        // The body is written verbatim, but not in deterministic encoding mode.
+        assert_eq!(to_vec(&envelope).unwrap(), &bytes[..]);
+        assert!(to_vec_canonical(&envelope).unwrap_err().is_syntax());
+
+        let value: Value = envelope.body.deserialize_into().unwrap();
+        assert_eq!(value, from_slice::<Value>(body).unwrap());
+    }
+
+    #[test]
+    fn test_raw_value_canonical() {
+        use serde::Serialize;
+        use serde_cbor::Serializer;
+
+        // {1000: 1, "a": 2} sorted bytewise and by length first
+        let bytewise = RawValue::from_vec(b"\xa2\x19\x03\xe8\x01\x61a\x02".to_vec()).unwrap();
+        let length_first = RawValue::from_vec(b"\xa2\x61a\x02\x19\x03\xe8\x01".to_vec()).unwrap();
+        assert_eq!(to_vec_canonical(&bytewise).unwrap(), bytewise.as_bytes());
+        assert!(to_vec_canonical(&length_first).is_err());
+
+        let mut vec = Vec::new();
+        let mut serializer = Serializer::new(&mut vec).legacy_canonical();
+        length_first.serialize(&mut serializer).unwrap();
+        assert!(bytewise.serialize(&mut serializer).is_err());
+        assert_eq!(vec, length_first.as_bytes());
+
+        // a float that fits into a shorter format
+        let float = RawValue::from_vec(b"\xfa\x3f\x80\x00\x00".to_vec()).unwrap();
+        assert!(to_vec_canonical(&float).is_err());
+    }
+
+    #[test]
+    fn test_raw_value_sequence() {
+        let bytes = b"\x83\x01\x82\x02\x03\x61a";
+        let values: Vec<RawValue> = from_slice(bytes).unwrap();
+        let values: Vec<&[u8]> = values.iter().map(RawValue::as_bytes).collect();
+        assert_eq!(values, [&b"\x01"[..], b"\x82\x02\x03", b"\x61a"]);
+    }
+
+    #[test]
+    fn test_raw_value_from_vec() {
+        let raw = RawValue::from_vec(vec![0x82, 0x01, 0x02]).unwrap();
+        assert_eq!(raw.deserialize_into::<(u8, u8)>().unwrap(), (1, 2));
+        assert_eq!(to_vec(&raw).unwrap(), [0x82, 0x01, 0x02]);
+        assert_eq!(format!("{:?}", raw), "RawValue(820102)");
+        assert_eq!(raw.into_vec(), [0x82, 0x01, 0x02]);
+
+        // Truncated items and trailing data are rejected.
+        assert!(RawValue::from_vec(vec![0x82, 0x01]).is_err());
+        assert!(RawValue::from_vec(vec![0x01, 0x02]).is_err());
+    }
+
+    #[test]
+    fn test_raw_value_invalid_item() {
+        // {"to": "bob", "body": "\xff"} with invalid UTF-8
+        let bytes = b"\xa2\x62to\x63bob\x64body\x61\xff";
+        assert!(from_slice::<Envelope>(bytes).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use serde::Serialize;
-use serde_cbor::ser::{Serializer, SliceWrite};
+use serde_cbor::ser::{SequenceSerializer, Serializer, SliceWrite};
 
 #[test]
 fn test_str() {
@@ -41,6 +41,20 @@ fn serialize_and_compare<T: Serialize>(value: T, expected: &[u8]) {
     assert_eq!(&slice[..end], expected);
 }
 
+#[test]
+fn test_sequence_slice() {
+    let mut slice = [0u8; 64];
+    let mut seq = SequenceSerializer::new(SliceWrite::new(&mut slice));
+    assert!(seq.is_empty());
+    seq.serialize("foobar").unwrap();
+    seq.serialize(&[1, 2, 3]).unwrap();
+    assert_eq!(seq.len(), 2);
+    assert_eq!(seq.bytes_written(), 11);
+    let writer = seq.into_inner();
+    let end = writer.bytes_written();
+    assert_eq!(&writer.into_inner()[..end], b"ffoobar\x83\x01\x02\x03");
+}
+
 #[cfg(feature = "std")]
 mod std_tests {
     use serde::Serializer;
@@ -251,4 +265,162 @@ mod std_tests {
         assert_eq!(vec, b"\xF9\x51\x50");
         assert_eq!(from_slice::<f32>(&vec[..]).unwrap(), 42.5f32);
     }
+
+    #[test]
+    fn test_sequence() {
+        use serde_cbor::{Deserializer, SequenceSerializer, Value};
+
+        let mut log = Vec::new();
+        let mut seq = SequenceSerializer::new(&mut log);
+        seq.serialize(&1).unwrap();
+        seq.serialize("two").unwrap();
+        seq.serialize(&vec![3, 4]).unwrap();
+        assert_eq!(seq.offsets(), &[0, 1, 5]);
+        let (_, offsets) = seq.into_parts();
+        assert_eq!(offsets, [0, 1, 5]);
+        assert_eq!(log, b"\x01\x63two\x82\x03\x04");
+
+        // the index is tagged, so readers can tell it apart from the items
+        let mut indexed = Vec::new();
+        let mut seq = SequenceSerializer::new(&mut indexed);
+        seq.serialize(&1).unwrap();
+        seq.serialize("two").unwrap();
+        seq.finish_with_index().unwrap();
+        assert_eq!(indexed, b"\x01\x63two\xda\x63\x62\x69\x78\x82\x00\x01");
+        if cfg!(feature = "tags") {
+            let items = Deserializer::from_slice(&indexed)
+                .into_iter::<Value>()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(
+                items[2],
+                Value::Tag(
+                    SequenceSerializer::<Vec<u8>>::INDEX_TAG,
+                    Box::new(Value::Array(vec![Value::Integer(0), Value::Integer(1)]))
+                )
+            );
+        }
+
+        // appending to an existing log
+        let mut seq = SequenceSerializer::from_serializer(ser::Serializer::new(&mut log));
+        seq.serialize(&true).unwrap();
+        assert_eq!(seq.bytes_written(), 1);
+
+        let items = Deserializer::from_slice(&log)
+            .into_iter::<Value>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[1], Value::Text("two".to_string()));
+        assert_eq!(items[3], Value::Bool(true));
+    }
+
+    /// A writer that records its calls and writes at most five bytes at once.
+    #[derive(Default)]
+    struct Recorder {
+        bytes: Vec<u8>,
+        calls: Vec<usize>,
+        // Fail once this many bytes have been written.
+        limit: Option<usize>,
+    }
+
+    impl std::io::Write for Recorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.write_vectored(&[std::io::IoSlice::new(buf)])
+        }
+
+        fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+            let max = match self.limit {
+                Some(limit) if self.bytes.len() >= limit => {
+                    return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "limit"));
+                }
+                Some(limit) => (limit - self.bytes.len()).min(5),
+                None => 5,
+            };
+            let mut n = 0;
+            for buf in bufs {
+                let len = buf.len().min(max - n);
+                self.bytes.extend_from_slice(&buf[..len]);
+                n += len;
+            }
+            self.calls.push(n);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_vectored_and_buffered() {
+        use serde::Serialize;
+        use serde_cbor::ser::{BufWrite, IoWrite};
+        use serde_cbor::Value;
+
+        let value = Value::Array(vec![
+            Value::Integer(1),
+            Value::Bytes(vec![7; 12]),
+            Value::Text("abc".to_string()),
+        ]);
+        let expected = to_vec(&value).unwrap();
+
+        // the head of the byte string is written together with its first bytes
+        let mut recorder = Recorder::default();
+        value
+            .serialize(&mut ser::Serializer::new(IoWrite::new(&mut recorder)))
+            .unwrap();
+        assert_eq!(recorder.bytes, expected);
+        assert_eq!(recorder.calls, [1, 1, 5, 5, 3, 4]);
+
+        // small writes are collected, the byte string is written along with the buffer
+        let mut recorder = Recorder::default();
+        let mut writer = BufWrite::with_capacity(8, IoWrite::new(&mut recorder));
+        value
+            .serialize(&mut ser::Serializer::new(&mut writer))
+            .unwrap();
+        assert_eq!(writer.buffer(), b"\x63abc");
+        writer.flush().unwrap();
+        drop(writer);
+        assert_eq!(recorder.bytes, expected);
+        assert_eq!(recorder.calls, [5, 5, 5, 4]);
+
+        // the buffered data is written when the writer is dropped
+        let mut recorder = Recorder::default();
+        let mut writer = BufWrite::with_capacity(8, IoWrite::new(&mut recorder));
+        value
+            .serialize(&mut ser::Serializer::new(&mut writer))
+            .unwrap();
+        drop(writer);
+        assert_eq!(recorder.bytes, expected);
+    }
+
+    #[test]
+    fn test_buffered_write_error() {
+        use serde_cbor::ser::{BufWrite, IoWrite, Write};
+
+        // a failed flush doesn't write the buffer again on drop
+        let mut recorder = Recorder {
+            limit: Some(3),
+            ..Recorder::default()
+        };
+        let mut writer = BufWrite::with_capacity(8, IoWrite::new(&mut recorder));
+        writer.write_all(b"\x01\x02\x03\x04\x05\x06").unwrap();
+        assert!(writer.flush().is_err());
+        assert!(writer.buffer().is_empty());
+        drop(writer);
+        assert_eq!(recorder.bytes, b"\x01\x02\x03");
+
+        // neither does a failed write of a large buffer
+        let mut recorder = Recorder {
+            limit: Some(7),
+            ..Recorder::default()
+        };
+        let mut writer = BufWrite::with_capacity(8, IoWrite::new(&mut recorder));
+        writer.write_all(b"\x01\x02\x03").unwrap();
+        assert!(writer.write_all(&[7; 10]).is_err());
+        assert!(writer.buffer().is_empty());
+        drop(writer);
+        assert_eq!(recorder.bytes, b"\x01\x02\x03\x07\x07\x07\x07");
+    }
 }
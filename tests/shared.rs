@@ -0,0 +1,75 @@
+#[cfg(all(feature = "std", feature = "bytes"))]
+mod std_tests {
+    use bytes::Bytes;
+    use serde_cbor::de::{from_bytes, Deserializer};
+    use serde_cbor::shared::SharedBytes;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Message {
+        id: u32,
+        payload: SharedBytes,
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        payload: &'a [u8],
+    }
+
+    fn contains(input: &Bytes, bytes: &SharedBytes) -> bool {
+        let start = input.as_ptr() as usize;
+        let ptr = bytes.as_ptr() as usize;
+        start <= ptr && ptr + bytes.len() <= start + input.len()
+    }
+
+    #[test]
+    fn test_shared_zero_copy() {
+        let message = Message {
+            id: 7,
+            payload: SharedBytes::from(vec![0xab; 100]),
+            name: "x".to_string(),
+        };
+        let input = Bytes::from(serde_cbor::to_vec(&message).unwrap());
+        let decoded: Message = from_bytes(&input).unwrap();
+        assert_eq!(decoded, message);
+        assert!(contains(&input, &decoded.payload));
+
+        // other targets still borrow from the input
+        let borrowed: Borrowed<'_> = from_bytes(&input).unwrap();
+        assert_eq!(borrowed.payload.as_ptr(), decoded.payload.as_ptr());
+
+        // the payload outlives the input
+        drop(input);
+        assert_eq!(&decoded.payload[..], &[0xab; 100][..]);
+    }
+
+    #[test]
+    fn test_shared_copy_fallback() {
+        let input = serde_cbor::to_vec(&SharedBytes::from(vec![1, 2, 3])).unwrap();
+        let bytes: SharedBytes = serde_cbor::from_slice(&input).unwrap();
+        assert_eq!(&bytes[..], [1, 2, 3]);
+
+        // indefinite-length byte strings are copied
+        let input = Bytes::from_static(b"\x5f\x41\x01\x42\x02\x03\xff");
+        let bytes: SharedBytes = from_bytes(&input).unwrap();
+        assert_eq!(&bytes[..], [1, 2, 3]);
+        assert!(!contains(&input, &bytes));
+    }
+
+    #[test]
+    fn test_shared_stream() {
+        let mut input = Vec::new();
+        for part in &[&b"ab"[..], b"", b"cde"] {
+            serde_cbor::to_writer(&mut input, &SharedBytes::from(part.to_vec())).unwrap();
+        }
+        let input = Bytes::from(input);
+        let parts = Deserializer::from_bytes(&input)
+            .into_iter::<SharedBytes>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(&parts[2][..], b"cde");
+        assert!(contains(&input, &parts[0]) && contains(&input, &parts[2]));
+    }
+}
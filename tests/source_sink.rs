@@ -0,0 +1,126 @@
+#[cfg(feature = "std")]
+mod std_tests {
+    use serde_cbor::de::{from_source, Deserializer, Source};
+    use serde_cbor::ser::{to_sink, Sink};
+    use serde_cbor::Value;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Record {
+        id: u64,
+        name: String,
+        data: Vec<u8>,
+    }
+
+    /// A ring buffer that only hands out the bytes up to the end of its storage at once.
+    struct Ring {
+        storage: [u8; 8],
+        start: usize,
+        len: usize,
+        input: Vec<u8>,
+    }
+
+    impl Ring {
+        fn new(input: Vec<u8>) -> Ring {
+            Ring {
+                storage: [0; 8],
+                start: 5,
+                len: 0,
+                input,
+            }
+        }
+    }
+
+    impl Source for Ring {
+        type Error = std::io::Error;
+
+        fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+            if self.len == 0 {
+                self.start = (self.start + 3) % self.storage.len();
+                let n = self.input.len().min(self.storage.len() - self.start);
+                for (slot, byte) in self.storage[self.start..]
+                    .iter_mut()
+                    .zip(self.input.drain(..n))
+                {
+                    *slot = byte;
+                }
+                self.len = n;
+            }
+            Ok(&self.storage[self.start..self.start + self.len])
+        }
+
+        fn consume(&mut self, n: usize) {
+            assert!(n <= self.len);
+            self.start += n;
+            self.len -= n;
+        }
+    }
+
+    /// A sink that writes into a fixed buffer and fails when it is full.
+    struct Fixed {
+        buf: [u8; 16],
+        len: usize,
+    }
+
+    impl Sink for Fixed {
+        type Error = serde_cbor::Error;
+
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+            let end = self.len + buf.len();
+            if end > self.buf.len() {
+                return Err(serde::ser::Error::custom("buffer full"));
+            }
+            self.buf[self.len..end].copy_from_slice(buf);
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_source_ring() {
+        let record = Record {
+            id: 1 << 40,
+            name: "a name longer than the ring".to_string(),
+            data: vec![7; 30],
+        };
+        let bytes = serde_cbor::to_vec(&record).unwrap();
+        let decoded: Record = from_source(Ring::new(bytes.clone())).unwrap();
+        assert_eq!(decoded, record);
+
+        let value: Value = from_source(&bytes[..]).unwrap();
+        assert_eq!(value, serde_cbor::from_slice::<Value>(&bytes).unwrap());
+
+        // truncated input and trailing data
+        let error = from_source::<Record, _>(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(error.is_eof());
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(from_source::<Record, _>(Ring::new(trailing)).is_err());
+    }
+
+    #[test]
+    fn test_source_stream() {
+        let mut ring = Ring::new(b"\x01\x63abc\x9f\x01\x02\xff\x19\x01\x00".to_vec());
+        let values = Deserializer::from_source(&mut ring)
+            .into_iter::<Value>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[1], Value::Text("abc".to_string()));
+        assert_eq!(values[3], Value::Integer(256));
+        assert!(ring.input.is_empty() && ring.len == 0);
+    }
+
+    #[test]
+    fn test_sink_fixed() {
+        let mut sink = Fixed {
+            buf: [0; 16],
+            len: 0,
+        };
+        to_sink(&mut sink, &vec!["ab", "cd"]).unwrap();
+        assert_eq!(&sink.buf[..sink.len], b"\x82\x62ab\x62cd");
+
+        let error = to_sink(&mut sink, &"too long to fit").unwrap_err();
+        assert_eq!(error.to_string(), "buffer full");
+    }
+}
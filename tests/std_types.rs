@@ -183,4 +183,34 @@ mod std_tests {
         "3BFFFFFFFFFFFFFFFF"
     );
     testcase!(test_u128, u128, 17, "11");
+    testcase!(
+        test_i128_bignum_a,
+        i128,
+        -18446744073709551617i128,
+        "c349010000000000000000"
+    );
+    testcase!(
+        test_i128_bignum_b,
+        i128,
+        -0x8000_0000_0000_0000_0000_0000_0000_0000,
+        "c3507fffffffffffffffffffffffffffffff"
+    );
+    testcase!(
+        test_i128_bignum_c,
+        i128,
+        0x7fff_ffff_ffff_ffff_ffff_ffff_ffff_ffff,
+        "c2507fffffffffffffffffffffffffffffff"
+    );
+    testcase!(
+        test_u128_bignum_a,
+        u128,
+        18446744073709551616,
+        "c249010000000000000000"
+    );
+    testcase!(
+        test_u128_bignum_b,
+        u128,
+        0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff,
+        "c250ffffffffffffffffffffffffffffffff"
+    );
 }
@@ -1,6 +1,7 @@
-#[cfg(feature = "tags")]
+#[cfg(all(feature = "tags", feature = "std"))]
 mod tagtests {
-    use serde_cbor::value::Value;
+    use serde_cbor::tags::Tagged;
+    use serde_cbor::value::{to_value, Value, ValueRef};
     use serde_cbor::{from_slice, to_vec};
 
     fn decode_hex(s: &str) -> std::result::Result<Vec<u8>, std::num::ParseIntError> {
@@ -45,4 +46,114 @@ C1                   # tag(1)
         assert_eq!(bytes1, bytes2);
         assert_eq!(value1, value2);
     }
+
+    #[test]
+    fn tagged_value_ref() {
+        // 24("text")
+        let bytes = b"\xd8\x18\x64text";
+        let value: ValueRef = from_slice(bytes).unwrap();
+        assert_eq!(value, ValueRef::Tag(24, Box::new(ValueRef::Text("text"))));
+        assert_eq!(
+            value.to_owned(),
+            Value::Tag(24, Box::new(Value::Text("text".to_owned())))
+        );
+        assert_eq!(to_vec(&value).unwrap(), &bytes[..]);
+    }
+
+    #[test]
+    fn tagged_roundtrip() {
+        // 1(2("foo"))
+        let bytes = b"\xc1\xc2\x63foo";
+        let tagged: Tagged<Tagged<String>> = from_slice(bytes).unwrap();
+        assert_eq!(tagged.tag, Some(1));
+        assert_eq!(tagged.value.tag, Some(2));
+        assert_eq!(tagged.value.value, "foo");
+        assert_eq!(to_vec(&tagged).unwrap(), &bytes[..]);
+
+        let untagged: Tagged<String> = from_slice(b"\x63foo").unwrap();
+        assert_eq!(untagged.tag, None);
+        assert_eq!(to_vec(&untagged).unwrap(), b"\x63foo");
+    }
+
+    #[test]
+    fn tagged_nested_values() {
+        // [1(2), 3], the tag of the first element must not leak to the second
+        let bytes = b"\x82\xc1\x02\x03";
+        let (first, second): (Tagged<u8>, Tagged<u8>) = from_slice(bytes).unwrap();
+        assert_eq!((first.tag, first.value), (Some(1), 2));
+        assert_eq!((second.tag, second.value), (None, 3));
+
+        // Types that don't expect a tag only see the value.
+        let values: Vec<String> = from_slice(b"\x82\xc1\x61a\x61b").unwrap();
+        assert_eq!(values, ["a", "b"]);
+    }
+
+    #[test]
+    fn tagged_to_value() {
+        let value = to_value(Tagged::new(Some(32), "http://a")).unwrap();
+        assert_eq!(
+            value,
+            Value::Tag(32, Box::new(Value::Text("http://a".to_owned())))
+        );
+        let tagged: Tagged<String> = serde_cbor::value::from_value(value).unwrap();
+        assert_eq!(tagged.tag, Some(32));
+    }
+
+    #[test]
+    fn tagged_self_describe() {
+        use serde::Deserialize;
+        use serde_cbor::Deserializer;
+
+        // 55799(1(2))
+        let bytes = b"\xd9\xd9\xf7\xc1\x02";
+        let value: Value = from_slice(bytes).unwrap();
+        assert_eq!(
+            value,
+            Value::Tag(55799, Box::new(Value::Tag(1, Box::new(Value::Integer(2)))))
+        );
+
+        let mut de = Deserializer::from_slice(bytes).strip_self_describe();
+        let value = Value::deserialize(&mut de).unwrap();
+        assert_eq!(value, Value::Tag(1, Box::new(Value::Integer(2))));
+
+        let mut de = Deserializer::from_slice(bytes).strip_self_describe();
+        let tagged = Tagged::<u8>::deserialize(&mut de).unwrap();
+        assert_eq!((tagged.tag, tagged.value), (Some(1), 2));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_current_cbor_tag() {
+        // tags are passed in-band, so the old side channel is always empty
+        let tagged: Tagged<u8> = from_slice(b"\xc1\x02").unwrap();
+        assert_eq!(tagged.tag, Some(1));
+        assert_eq!(serde_cbor::tags::current_cbor_tag(), None);
+    }
+
+    #[test]
+    fn test_tagged_json() {
+        // other data formats only see the value
+        let tagged = Tagged::new(Some(32), "http://example.com".to_string());
+        let json = serde_json::to_string(&tagged).unwrap();
+        assert_eq!(json, r#""http://example.com""#);
+        let tagged: Tagged<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(tagged.tag, None);
+        assert_eq!(tagged.value, "http://example.com");
+
+        let value = Value::Tag(1, Box::new(Value::Integer(5)));
+        assert_eq!(serde_json::to_string(&value).unwrap(), "5");
+
+        // nested tags and tags with all digits set are written in full
+        let tagged = Tagged::new(Some(0xffff_ffff_ffff_ffff), Tagged::new(Some(1), 5));
+        let bytes = to_vec(&tagged).unwrap();
+        assert_eq!(bytes, b"\xdb\xff\xff\xff\xff\xff\xff\xff\xff\xc1\x05");
+        assert_eq!(
+            to_value(&tagged).unwrap(),
+            Value::Tag(
+                0xffff_ffff_ffff_ffff,
+                Box::new(Value::Tag(1, Box::new(Value::Integer(5))))
+            )
+        );
+        assert_eq!(serde_json::to_string(&tagged).unwrap(), "5");
+    }
 }
@@ -0,0 +1,69 @@
+#[cfg(all(feature = "tags", feature = "std"))]
+mod tagtests {
+    use serde_cbor::tags::{Base64Url, Tag, TaggedValue, Uri};
+    use serde_cbor::{from_slice, to_vec};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[test]
+    fn test_tagged_value_roundtrip() {
+        // 32("http://www.example.com")
+        let bytes = b"\xd8\x20\x76http://www.example.com";
+        let uri: TaggedValue<Uri, String> = from_slice(bytes).unwrap();
+        assert_eq!(uri.value, "http://www.example.com");
+        assert_eq!(to_vec(&uri).unwrap(), &bytes[..]);
+    }
+
+    #[test]
+    fn test_tagged_value_mismatch() {
+        // 33("aGVsbG8")
+        let bytes = b"\xd8\x21\x67aGVsbG8";
+        assert!(from_slice::<TaggedValue<Uri, String>>(bytes).is_err());
+        assert!(from_slice::<TaggedValue<Base64Url, String>>(bytes).is_ok());
+        // an untagged string
+        assert!(from_slice::<TaggedValue<Uri, String>>(b"\x67aGVsbG8").is_err());
+    }
+
+    #[test]
+    fn test_custom_tag() {
+        enum Ipv4 {}
+
+        impl Tag for Ipv4 {
+            const NUMBER: u64 = 52;
+        }
+
+        let address = TaggedValue::<Ipv4, _>::new([192u8, 0, 2, 1]);
+        let bytes = to_vec(&address).unwrap();
+        assert_eq!(bytes, b"\xd8\x34\x84\x18\xc0\x00\x02\x01");
+        let decoded: TaggedValue<Ipv4, [u8; 4]> = from_slice(&bytes).unwrap();
+        assert_eq!(decoded, address);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Link {
+        #[serde(with = "serde_cbor::tags::uri")]
+        href: String,
+        #[serde(with = "serde_cbor::tags::mime")]
+        body: String,
+        #[serde(with = "serde_cbor::tags::regex")]
+        pattern: String,
+    }
+
+    #[test]
+    fn test_with_modules() {
+        let link = Link {
+            href: "http://a".to_string(),
+            body: "b".to_string(),
+            pattern: "c".to_string(),
+        };
+        let bytes = to_vec(&link).unwrap();
+        assert_eq!(
+            bytes,
+            &b"\xa3\x64href\xd8\x20\x68http://a\x64body\xd8\x24\x61b\x67pattern\xd8\x23\x61c"[..]
+        );
+        assert_eq!(from_slice::<Link>(&bytes).unwrap(), link);
+
+        // the regex tag is missing
+        let bytes = b"\xa3\x64href\xd8\x20\x68http://a\x64body\xd8\x24\x61b\x67pattern\x61c";
+        assert!(from_slice::<Link>(bytes).is_err());
+    }
+}
@@ -23,7 +23,7 @@ mod std_tests {
         unit_array: Vec<UnitStruct>,
     }
 
-    use serde_cbor::value::Value;
+    use serde_cbor::value::{BigInt, Value, ValueRef};
     use std::iter::FromIterator;
 
     #[test]
@@ -97,4 +97,185 @@ mod std_tests {
         let reference = b"\xa2\x00\x11\x01\x18\x2a";
         assert_eq!(data, reference);
     }
+
+    #[test]
+    fn value_ref_borrows() {
+        // [h'0102', {"a": -2, "a": 1.5}, "text", null, true]
+        let data = b"\x85\x42\x01\x02\xa2\x61a\x21\x61a\xf9\x3e\x00\x64text\xf6\xf5";
+        let value: ValueRef = serde_cbor::from_slice(data).unwrap();
+        let expected = ValueRef::Array(vec![
+            ValueRef::Bytes(&data[2..4]),
+            ValueRef::Map(vec![
+                (ValueRef::Text("a"), ValueRef::Integer(-2)),
+                (ValueRef::Text("a"), ValueRef::Float(1.5)),
+            ]),
+            ValueRef::Text("text"),
+            ValueRef::Null,
+            ValueRef::Bool(true),
+        ]);
+        assert_eq!(value, expected);
+        match value {
+            ValueRef::Array(ref values) => match values[0] {
+                ValueRef::Bytes(bytes) => assert_eq!(bytes.as_ptr(), data[2..].as_ptr()),
+                _ => panic!("expected bytes"),
+            },
+            _ => panic!("expected an array"),
+        }
+
+        // The value serializes to the same bytes.
+        assert_eq!(serde_cbor::to_vec(&value).unwrap(), &data[..]);
+    }
+
+    #[test]
+    fn value_ref_to_owned() {
+        // [h'0102', {"a": -2, "a": 1}, "text"]
+        let data = b"\x83\x42\x01\x02\xa2\x61a\x21\x61a\x01\x64text";
+        let value: ValueRef = serde_cbor::from_slice(data).unwrap();
+        let mut map = BTreeMap::new();
+        map.insert(Value::Text("a".to_owned()), Value::Integer(1));
+        let expected = Value::Array(vec![
+            Value::Bytes(vec![1, 2]),
+            Value::Map(map),
+            Value::Text("text".to_owned()),
+        ]);
+        assert_eq!(value.to_owned(), expected);
+        assert_eq!(
+            value.to_owned(),
+            serde_cbor::from_slice::<Value>(data).unwrap()
+        );
+    }
+
+    #[test]
+    fn value_ref_indefinite_string() {
+        // (_ "a", "b")
+        let data = b"\x7f\x61a\x61b\xff";
+        assert!(serde_cbor::from_slice::<ValueRef>(data).is_err());
+
+        let mut buf = data.to_vec();
+        let mut deserializer = serde_cbor::Deserializer::from_mut_slice(&mut buf);
+        let value: ValueRef = serde::Deserialize::deserialize(&mut deserializer).unwrap();
+        assert_eq!(value, ValueRef::Text("ab"));
+    }
+
+    #[test]
+    fn simple_values_roundtrip() {
+        // [undefined, simple(0), simple(16), simple(255), null, true]
+        let data = b"\x86\xf7\xe0\xf0\xf8\xff\xf6\xf5";
+        let value: Value = serde_cbor::from_slice(data).unwrap();
+        let expected = Value::Array(vec![
+            Value::Undefined,
+            Value::Simple(0),
+            Value::Simple(16),
+            Value::Simple(255),
+            Value::Null,
+            Value::Bool(true),
+        ]);
+        assert_eq!(value, expected);
+        assert_eq!(serde_cbor::to_vec(&value).unwrap(), &data[..]);
+        assert_eq!(serde_cbor::value::to_value(&value).unwrap(), expected);
+
+        let value: ValueRef = serde_cbor::from_slice(data).unwrap();
+        assert_eq!(serde_cbor::to_vec(&value).unwrap(), &data[..]);
+        assert_eq!(value.to_owned(), expected);
+
+        // Simple values are only accepted by `Value`.
+        assert!(serde_cbor::from_slice::<u8>(b"\xf0").is_err());
+        serde_cbor::from_slice::<()>(b"\xf7").unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<Vec<Value>>(b"\x81\xf0").unwrap(),
+            vec![Value::Simple(16)]
+        );
+    }
+
+    #[test]
+    fn simple_values_invalid() {
+        assert!(serde_cbor::to_vec(&Value::Simple(24)).is_err());
+        assert!(serde_cbor::to_vec(&Value::Simple(31)).is_err());
+        // Simple values below 32 must be encoded in one byte.
+        assert!(serde_cbor::from_slice::<Value>(b"\xf8\x10").is_err());
+
+        // Reserved simple values can still be compared and used as keys.
+        assert!(Value::Simple(24) < Value::Simple(25));
+        assert!(Value::Undefined < Value::Simple(24));
+        assert!(Value::Simple(31) < Value::Float(0.0));
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(Value::Simple(25), 1);
+        map.insert(Value::Simple(24), 2);
+        map.insert(Value::Bool(true), 3);
+        let keys: Vec<_> = map.keys().cloned().collect();
+        assert_eq!(
+            keys,
+            [Value::Bool(true), Value::Simple(24), Value::Simple(25)]
+        );
+    }
+
+    #[test]
+    fn bignums() {
+        // [2^64, -2^64 - 1, 2^128, -2^128 - 1, 2(h'00ff')]
+        let data = b"\x85\xc2\x49\x01\x00\x00\x00\x00\x00\x00\x00\x00\xc3\x49\x01\x00\x00\x00\x00\x00\x00\x00\x00\xc2\x51\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\xc3\x51\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\xc2\x42\x00\xff";
+        let mut big = vec![0; 17];
+        big[0] = 1;
+        let expected = Value::Array(vec![
+            Value::Integer(1 << 64),
+            Value::Integer(-(1 << 64) - 1),
+            Value::BigInt(BigInt::new(false, big.clone())),
+            Value::BigInt(BigInt::new(true, big)),
+            Value::Integer(255),
+        ]);
+        let value: Value = serde_cbor::from_slice(data).unwrap();
+        assert_eq!(value, expected);
+        let value: ValueRef = serde_cbor::from_slice(data).unwrap();
+        assert_eq!(value.to_owned(), expected);
+
+        // Bignums are written in their shortest form.
+        let mut encoded = data[..data.len() - 4].to_vec();
+        encoded.extend_from_slice(b"\x18\xff");
+        assert_eq!(serde_cbor::to_vec(&expected).unwrap(), encoded);
+        assert_eq!(serde_cbor::value::to_value(&expected).unwrap(), expected);
+
+        let max = Value::BigInt(BigInt::new(false, vec![0xff; 16]));
+        assert_eq!(
+            Value::from(0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff_u128),
+            max
+        );
+        assert_eq!(
+            serde_cbor::value::to_value(0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff_u128).unwrap(),
+            max
+        );
+    }
+
+    #[test]
+    fn bignums_ordering() {
+        let mut map = BTreeMap::new();
+        map.insert(
+            Value::Integer(-0x8000_0000_0000_0000_0000_0000_0000_0000),
+            0,
+        );
+        map.insert(Value::Integer(1 << 70), 1);
+        map.insert(Value::Integer(-1), 2);
+        map.insert(Value::Integer(1), 3);
+        let keys: Vec<_> = map.values().cloned().collect();
+        assert_eq!(keys, [3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn bigint_strings() {
+        let cases = [
+            "0",
+            "-1",
+            "255",
+            "-256",
+            "340282366920938463463374607431768211456",
+            "-340282366920938463463374607431768211457",
+        ];
+        for &s in cases.iter() {
+            let v: BigInt = s.parse().unwrap();
+            assert_eq!(v.to_string(), s);
+        }
+        assert_eq!("-256".parse::<BigInt>().unwrap(), BigInt::from(-256));
+        assert_eq!("+7".parse::<BigInt>().unwrap(), BigInt::from(7));
+        assert!("".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+        assert!("1_000".parse::<BigInt>().is_err());
+    }
 }